@@ -0,0 +1,87 @@
+use criterion::{criterion_group, Criterion};
+
+mod combine_benches {
+    use super::*;
+    use halo2wrong::curves::bn256::{Fr as BnScalar, G1Affine as BnG1};
+    use halo2wrong::curves::group::Curve;
+    use halo2wrong::halo2::arithmetic::Field;
+    use rand_core::OsRng;
+    use zkrand::dkg::{DkgConfig, EvalDomain, PartialEval};
+    use zkrand::{combine_partial_evaluations, shares, DkgShareKey};
+
+    // the previous interpolation: one inversion per Lagrange coefficient,
+    // t scalar multiplications and a fold
+    fn interpolate_naive(sigmas: &[PartialEval]) -> BnG1 {
+        let indices: Vec<_> = sigmas
+            .iter()
+            .map(|sigma| BnScalar::from(sigma.index as u64))
+            .collect();
+        let mut lambdas = vec![];
+        for i in indices.iter() {
+            let mut numerator = BnScalar::one();
+            let mut denominator = BnScalar::one();
+            for k in indices.iter() {
+                if !k.eq(i) {
+                    numerator = numerator * k;
+                    denominator = denominator * (k - i);
+                }
+            }
+            lambdas.push(numerator * denominator.invert().unwrap());
+        }
+
+        let pis: Vec<_> = sigmas
+            .iter()
+            .zip(lambdas.iter())
+            .map(|(sigma, lambda)| sigma.value * lambda)
+            .collect();
+        pis.iter()
+            .skip(1)
+            .fold(pis[0], |sum, p| sum + p)
+            .to_affine()
+    }
+
+    fn combine<const THRESHOLD: usize, const NUMBER_OF_MEMBERS: usize>(c: &mut Criterion) {
+        let mut rng = OsRng;
+        // simplified setup only used for benchmark
+        let g = BnG1::generator();
+        let coeffs: Vec<_> = (0..THRESHOLD).map(|_| BnScalar::random(&mut rng)).collect();
+        let shares = shares(NUMBER_OF_MEMBERS, &coeffs);
+        let keys: Vec<_> = shares
+            .iter()
+            .enumerate()
+            .map(|(i, s)| DkgShareKey::new(i + 1, *s, (g * s).to_affine()))
+            .collect();
+
+        let input = b"test first random";
+        let evals: Vec<_> = keys[0..THRESHOLD]
+            .iter()
+            .map(|key| key.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+
+        let dkg_config = DkgConfig::new(THRESHOLD, NUMBER_OF_MEMBERS).unwrap();
+        let pseudo = combine_partial_evaluations(&dkg_config, &evals).unwrap();
+        assert_eq!(pseudo.proof, interpolate_naive(&evals));
+
+        let mut group = c.benchmark_group(format!(
+            "combine partial evaluation ({THRESHOLD}, {NUMBER_OF_MEMBERS})"
+        ));
+        group.bench_function("naive", |b| b.iter(|| interpolate_naive(&evals)));
+        group.bench_function("msm", |b| {
+            b.iter(|| combine_partial_evaluations(&dkg_config, &evals).unwrap())
+        });
+        group.finish();
+    }
+
+    criterion_group! {
+        name = combine_benches;
+        config = Criterion::default();
+        targets =
+            combine::<3,5>,
+            combine::<9,16>,
+            combine::<20,38>,
+            combine::<42,83>,
+            combine::<86,171>,
+    }
+}
+
+criterion::criterion_main!(combine_benches::combine_benches);
@@ -7,7 +7,7 @@ mod dvrf_benches {
     use halo2wrong::halo2::arithmetic::Field;
     use rand_core::OsRng;
     use sha3::{Digest, Keccak256};
-    use zkrand::dkg::DkgConfig;
+    use zkrand::dkg::{DkgConfig, EvalDomain};
     use zkrand::{
         combine_partial_evaluations, hash_to_curve_bn, keygen, shares, DkgShareKey, PseudoRandom,
         EVAL_PREFIX,
@@ -23,7 +23,7 @@ mod dvrf_benches {
         let x = b"the first random 20230703";
 
         c.bench_function("dvrf partial evaluation", move |b| {
-            b.iter(|| key.evaluate(x, &mut rng))
+            b.iter(|| key.evaluate(&EvalDomain::default(), x, &mut rng))
         });
     }
 
@@ -44,14 +44,14 @@ mod dvrf_benches {
 
         let evals: Vec<_> = keys
             .iter()
-            .map(|key| key.evaluate(input, &mut rng))
+            .map(|key| key.evaluate(&EvalDomain::default(), input, &mut rng))
             .collect();
 
         let dkg_config = DkgConfig::new(THRESHOLD, NUMBER_OF_MEMBERS).unwrap();
-        let res = evals
-            .iter()
-            .zip(vks.iter())
-            .all(|(e, vk)| e.verify(&dkg_config, input, vk).is_ok());
+        let res = evals.iter().zip(vks.iter()).all(|(e, vk)| {
+            e.verify(&dkg_config, &EvalDomain::default(), input, vk)
+                .is_ok()
+        });
 
         assert!(res);
 
@@ -69,11 +69,15 @@ mod dvrf_benches {
         // verification time of partial evaluation independent of the values of threshold and number of members
         let key = DkgShareKey::new(index, sk, vk);
         let input = b"the first random 20230703";
-        let sigma = key.evaluate(input, &mut rng);
+        let sigma = key.evaluate(&EvalDomain::default(), input, &mut rng);
 
         let dkg_config = DkgConfig::new(4, 6).unwrap();
         c.bench_function("dvrf partial evaluation verify", move |b| {
-            b.iter(|| sigma.verify(&dkg_config, input, &vk).unwrap())
+            b.iter(|| {
+                sigma
+                    .verify(&dkg_config, &EvalDomain::default(), input, &vk)
+                    .unwrap()
+            })
         });
     }
 
@@ -98,7 +102,7 @@ mod dvrf_benches {
         let pr = PseudoRandom::new(proof, value);
 
         c.bench_function("dvrf pseudorandom verification", move |b| {
-            b.iter(|| pr.verify(input, &gpk).unwrap())
+            b.iter(|| pr.verify(&EvalDomain::default(), input, &gpk).unwrap())
         });
     }
 
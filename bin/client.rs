@@ -1,14 +1,7 @@
 use crate::mock::{mock_dkg, mock_members, mock_random};
-use crate::proof::{create_proof_checked, verify_single};
-use crate::serialise::{
-    hex_to_le_bytes, le_bytes_to_hex, DkgGlobalPubParams as DkgGlobalPubParamsSerde,
-    DkgMemberParams as DkgMemberParamsSerde, DkgShareKey as DkgShareKeySerde,
-    MemberKey as MemberKeySerde, PartialEval as PartialEvalSerde, Point, Point2,
-    PseudoRandom as PseudoRandomSerde,
-};
 use anyhow::{anyhow, Result};
 use ark_std::{end_timer, start_timer};
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use const_format::formatcp;
 use dotenv::dotenv;
 use halo2_ecc::halo2::halo2curves::bn256::{Fr as BnScalar, G1Affine as BnG1, G2Affine as BnG2};
@@ -21,23 +14,26 @@ use log::info;
 use pretty_env_logger;
 use rand_chacha::ChaCha20Rng;
 use rand_core::{OsRng, RngCore, SeedableRng};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::env;
-use std::fs::{create_dir_all, read, read_to_string, write};
-
-#[cfg(not(feature = "g2chip"))]
-use serialise::DkgMemberPublicParams as DkgMemberPublicParamsSerde;
+use std::fs::{create_dir_all, read, read_dir, read_to_string, remove_file, write};
+use std::path::Path;
 
-use zkrand::dkg::{DkgConfig, PartialEval};
+use zkrand::dkg::{DkgConfig, EvalDomain, PartialEval};
+use zkrand::keystore::{Kdf, Keystore};
+use zkrand::serialise::{hex_to_le_bytes, le_bytes_to_hex, Point, Point2};
+use zkrand::wire::{Wire, WireBytes};
 use zkrand::{
-    combine_partial_evaluations, dkg_global_public_params, load_or_create_params,
-    load_or_create_pk, load_or_create_vk, DkgGlobalPubParams, DkgMemberParams,
-    DkgMemberPublicParams, DkgShareKey, MemberKey, PseudoRandom,
+    check_verify_keys, combine_partial_evaluations_robust, create_proof_checked,
+    dkg_global_public_params, load_or_create_params, load_or_create_pk, load_or_create_pk_of,
+    load_or_create_vk, load_or_create_vk_of, plan, qualified_dealers, refresh_global_public_params,
+    reshare_global_public_params, verify_batch, verify_single, CircuitKind, Complaint,
+    DkgGlobalPubParams, DkgMemberParams, DkgMemberPublicParams, DkgShareKey, MemberKey,
+    PseudoRandom, QualifiedPolicy, QualifiedSet, ReshareDealing, Secret,
 };
 
 mod mock;
-mod proof;
-mod serialise;
 
 const KZG_PARAMS_DIR: &str = "./kzg_params";
 const CONTRACT_DIR: &str = "./contracts";
@@ -48,7 +44,13 @@ const DKG_DIR: &str = formatcp!("{}/dkg", DATA_DIR);
 const DKG_SECRETS_DIR: &str = formatcp!("{}/secrets", DKG_DIR);
 const DKG_PROOFS_DIR: &str = formatcp!("{}/proofs", DKG_DIR);
 const DKG_SHARES_DIR: &str = formatcp!("{}/shares", DKG_DIR);
+const DKG_COMPLAINTS_DIR: &str = formatcp!("{}/complaints", DKG_DIR);
+const REFRESH_DIR: &str = formatcp!("{}/refresh", DKG_DIR);
 const RANDOM_DIR: &str = "./data/random";
+const RESHARE_DIR: &str = formatcp!("{}/reshare", DATA_DIR);
+const RESHARE_PUBLIC_KEYS_PATH: &str = formatcp!("{}/mpks.json", RESHARE_DIR);
+const RESHARE_PROOFS_DIR: &str = formatcp!("{}/proofs", RESHARE_DIR);
+const RESHARE_SHARES_DIR: &str = formatcp!("{}/shares", RESHARE_DIR);
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -56,6 +58,176 @@ const RANDOM_DIR: &str = "./data/random";
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Encoding of the files written for keys, instances and evaluations; either encoding is read
+    #[arg(long, value_enum, global = true, default_value_t = Format::Json)]
+    format: Format,
+    #[command(flatten)]
+    password: PasswordArgs,
+    #[command(flatten)]
+    domain: DomainArgs,
+}
+
+#[derive(Debug, Args)]
+struct DomainArgs {
+    /// Chain id of the evaluation domain; partial evaluations use the default domain without it
+    #[arg(long, global = true, requires = "contract")]
+    chain_id: Option<u64>,
+    /// Address of the contract consuming the pseudorandom values, in hex
+    #[arg(long, global = true, value_name = "ADDRESS", requires = "chain_id")]
+    contract: Option<String>,
+    /// Application tag of the evaluation domain
+    #[arg(long, global = true, requires = "chain_id")]
+    app: Option<String>,
+}
+
+impl DomainArgs {
+    // PseudoRand.sol verifying the evaluations has to be deployed with domain.prefix()
+    fn domain(&self) -> Result<EvalDomain> {
+        let (chain_id, contract) = match (self.chain_id, &self.contract) {
+            (Some(chain_id), Some(contract)) => (chain_id, contract),
+            _ => return Ok(EvalDomain::default()),
+        };
+
+        let address = hex::decode(contract.trim_start_matches("0x"))?;
+        let address: [u8; 20] = address
+            .try_into()
+            .map_err(|_| anyhow!("Contract address must have 20 bytes"))?;
+        let app = self.app.as_deref().unwrap_or("");
+
+        Ok(EvalDomain::new(chain_id, &address, app))
+    }
+}
+
+#[derive(Debug, Args)]
+struct PasswordArgs {
+    /// Password of the keystores for member keys and secret shares; keys are written in plaintext without a password
+    #[arg(long, global = true, conflicts_with_all = ["password_env", "password_file"])]
+    password: Option<String>,
+    /// Read the keystore password from the environment variable
+    #[arg(
+        long,
+        global = true,
+        value_name = "VAR",
+        conflicts_with = "password_file"
+    )]
+    password_env: Option<String>,
+    /// Read the keystore password from the first line of the file
+    #[arg(long, global = true, value_name = "PATH")]
+    password_file: Option<String>,
+    /// Key derivation function of new keystores
+    #[arg(long, value_enum, global = true, default_value_t = KdfArg::Scrypt)]
+    kdf: KdfArg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum KdfArg {
+    Scrypt,
+    Pbkdf2,
+}
+
+impl PasswordArgs {
+    fn password(&self) -> Result<Option<Secret<String>>> {
+        if let Some(password) = &self.password {
+            return Ok(Some(Secret::new(password.clone())));
+        }
+        if let Some(var) = &self.password_env {
+            let password =
+                env::var(var).map_err(|_| anyhow!("Environment variable {var} not set"))?;
+            return Ok(Some(Secret::new(password)));
+        }
+        if let Some(path) = &self.password_file {
+            let contents = Secret::new(read_to_string(path)?);
+            let password = contents.expose_secret().lines().next().unwrap_or("");
+            return Ok(Some(Secret::new(password.to_string())));
+        }
+
+        Ok(None)
+    }
+
+    fn kdf(&self) -> Kdf {
+        match self.kdf {
+            KdfArg::Scrypt => Kdf::scrypt(),
+            KdfArg::Pbkdf2 => Kdf::pbkdf2(),
+        }
+    }
+}
+
+// how member keys and secret shares are written and read
+struct KeyOptions {
+    format: Format,
+    password: PasswordArgs,
+    kdf: Kdf,
+}
+
+impl KeyOptions {
+    // the password is only resolved by commands that write or read keys
+    fn password(&self) -> Result<Option<Secret<String>>> {
+        self.password.password()
+    }
+}
+
+trait SecretKey: Serialize + DeserializeOwned + Wire {
+    fn encrypt(
+        &self,
+        password: &[u8],
+        kdf: Kdf,
+        rng: impl RngCore,
+    ) -> Result<Keystore, zkrand::Error>;
+    fn decrypt(keystore: &Keystore, password: &[u8]) -> Result<Self, zkrand::Error>;
+}
+
+impl SecretKey for MemberKey {
+    fn encrypt(
+        &self,
+        password: &[u8],
+        kdf: Kdf,
+        rng: impl RngCore,
+    ) -> Result<Keystore, zkrand::Error> {
+        MemberKey::encrypt(self, password, kdf, rng)
+    }
+
+    fn decrypt(keystore: &Keystore, password: &[u8]) -> Result<Self, zkrand::Error> {
+        keystore.decrypt_member_key(password)
+    }
+}
+
+impl SecretKey for DkgShareKey {
+    fn encrypt(
+        &self,
+        password: &[u8],
+        kdf: Kdf,
+        rng: impl RngCore,
+    ) -> Result<Keystore, zkrand::Error> {
+        DkgShareKey::encrypt(self, password, kdf, rng)
+    }
+
+    fn decrypt(keystore: &Keystore, password: &[u8]) -> Result<Self, zkrand::Error> {
+        keystore.decrypt_share_key(password)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    /// Hex json
+    Json,
+    /// Versioned binary encoding with compressed points
+    Binary,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Binary => "bin",
+        }
+    }
+
+    fn other(&self) -> Format {
+        match self {
+            Format::Json => Format::Binary,
+            Format::Binary => Format::Json,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -81,6 +253,35 @@ enum Commands {
     Dkg(DkgArgs),
     /// Random commands
     Rand(RandArgs),
+    /// Refresh commands
+    Refresh(RefreshArgs),
+    /// Reshare commands
+    Reshare(ReshareArgs),
+    /// Encrypt the plaintext member keys and secret shares with the keystore password
+    Migrate,
+    /// Measure the circuit for (THRESHOLD, NUMBER_OF_MEMBERS) and estimate the degree and key sizes
+    Plan {
+        /// The circuit to measure
+        #[arg(long, value_enum, default_value_t = CircuitArg::Dkg)]
+        circuit: CircuitArg,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum CircuitArg {
+    Dkg,
+    Compressed,
+    Refresh,
+}
+
+impl CircuitArg {
+    fn kind(&self) -> CircuitKind {
+        match self {
+            CircuitArg::Dkg => CircuitKind::Dkg,
+            CircuitArg::Compressed => CircuitKind::Compressed,
+            CircuitArg::Refresh => CircuitKind::Refresh,
+        }
+    }
 }
 
 #[derive(Debug, Args)]
@@ -97,7 +298,78 @@ enum DkgCommands {
     Prove { index: usize },
     /// Verify the snark proof for dkg public parameters for member i
     Verify { index: usize },
+    /// Batch verify the snark proofs of all dealers in "all_instances.json"
+    VerifyAll,
     /// Derive the global public parameters and (if index is given) the secret share for member i
+    /// from the qualified dealers
+    Derive {
+        index: Option<usize>,
+        /// Read the member's secret key from "zkdvrf/data/members/<file>.json"
+        #[arg(short, default_value = "member")]
+        file: Option<String>,
+        /// Minimum number of qualified dealers, defaults to the number of members
+        #[arg(short)]
+        min_dealers: Option<usize>,
+    },
+    /// Check the shares dealt to member i and create complaints against the dealers of invalid shares
+    Complain {
+        index: usize,
+        /// Read the member's secret key from "zkdvrf/data/members/<file>.json"
+        #[arg(short, default_value = "member")]
+        file: Option<String>,
+    },
+    /// Verify the complaint of member i against dealer j
+    VerifyComplaint { accuser: usize, dealer: usize },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = true)]
+struct RefreshArgs {
+    #[command(subcommand)]
+    command: RefreshCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum RefreshCommands {
+    /// Create a refresh dealing and a snark proof for member i
+    Prove { index: usize },
+    /// Verify the snark proof of the refresh dealing of member i
+    Verify { index: usize },
+    /// Verify all the refresh dealings and update the global public parameters or, if index is
+    /// given, the secret share of member i
+    Derive {
+        index: Option<usize>,
+        /// Read the member's secret key from "zkdvrf/data/members/<file>.json"
+        #[arg(short, default_value = "member")]
+        file: Option<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(flatten_help = true)]
+struct ReshareArgs {
+    /// Threshold of the new committee
+    #[arg(long)]
+    new_threshold: u32,
+    /// Number of members of the new committee
+    #[arg(long)]
+    new_members: u32,
+    /// Degree of the NIDKG circuit of the new committee, planned if not given
+    #[arg(long)]
+    new_degree: Option<u32>,
+    #[command(subcommand)]
+    command: ReshareCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum ReshareCommands {
+    /// Deal the secret share of member i of the old committee to the new committee with a snark proof
+    Prove { index: usize },
+    /// Verify the resharing dealing of member i of the old committee
+    Verify { index: usize },
+    /// Verify the resharing dealings and derive the global public parameters of the new committee
+    /// and (if index is given) the secret share for member i of the new committee
     Derive {
         index: Option<usize>,
         /// Read the member's secret key from "zkdvrf/data/members/<file>.json"
@@ -120,6 +392,9 @@ enum RandCommands {
     Eval {
         index: usize,
         input: String,
+        /// Derive the nonce of the proof from the secret share and the input instead of the rng
+        #[arg(short, long, default_value_t = false)]
+        deterministic: bool,
     },
     /// Verify the partial evaluation on input string for member i
     Verify {
@@ -155,7 +430,7 @@ struct MockArgs {
 struct ParamsConfig {
     threshold: u32,
     number_of_members: u32,
-    degree: u32,
+    degree: Option<u32>,
 }
 
 impl ParamsConfig {
@@ -164,29 +439,175 @@ impl ParamsConfig {
 
         Ok(config)
     }
+
+    // DEGREE, or the minimum degree of the circuit if DEGREE is not given; the circuit is only
+    // measured by the commands that load kzg parameters or keys
+    pub fn degree(&self, kind: CircuitKind) -> Result<u32> {
+        if let Some(degree) = self.degree {
+            return Ok(degree);
+        }
+
+        let degree = plan(self.dkg_config()?, kind)?.k;
+        info!("DEGREE is missing or invalid. Using the planned degree {degree}");
+        Ok(degree)
+    }
+}
+
+// write value to "<stem>.json" or "<stem>.bin" and return the path
+fn save_artifact<T: Serialize + Wire>(stem: &str, value: &T, format: Format) -> Result<String> {
+    let path = format!("{stem}.{}", format.extension());
+    match format {
+        Format::Json => write(&path, serde_json::to_string(value)?)?,
+        Format::Binary => write(&path, value.to_wire().wire_bytes())?,
+    }
+    Ok(path)
+}
+
+// read "<stem>.json" or "<stem>.bin", preferring the given format if both exist
+fn read_artifact<T: DeserializeOwned + Wire>(stem: &str, format: Format) -> Result<T> {
+    for f in [format, format.other()] {
+        let path = format!("{stem}.{}", f.extension());
+        if !Path::new(&path).exists() {
+            continue;
+        }
+        let value = match f {
+            Format::Json => serde_json::from_str(&read_to_string(&path)?)?,
+            Format::Binary => T::from_wire(&read(&path)?)?,
+        };
+        return Ok(value);
+    }
+
+    Err(anyhow!("Neither {stem}.json nor {stem}.bin exists"))
+}
+
+// with a password the key is written as an encrypted keystore to "<stem>.json"
+fn save_key<T: SecretKey>(
+    stem: &str,
+    key: &T,
+    options: &KeyOptions,
+    rng: impl RngCore,
+) -> Result<String> {
+    match options.password()? {
+        Some(password) => {
+            let keystore = key.encrypt(password.expose_secret().as_bytes(), options.kdf, rng)?;
+            let path = format!("{stem}.json");
+            write(&path, serde_json::to_string_pretty(&keystore)?)?;
+            Ok(path)
+        }
+        None => save_artifact(stem, key, options.format),
+    }
+}
+
+fn read_keystore(path: &str) -> Option<Keystore> {
+    let contents = read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+// encrypted keystores are detected by their contents, plaintext keys are read as before
+fn read_key<T: SecretKey>(stem: &str, options: &KeyOptions) -> Result<T> {
+    let path = format!("{stem}.json");
+    if let Some(keystore) = read_keystore(&path) {
+        let password = options.password()?.ok_or_else(|| {
+            anyhow!("{path} is encrypted; provide --password, --password-env or --password-file")
+        })?;
+        return Ok(T::decrypt(&keystore, password.expose_secret().as_bytes())?);
+    }
+
+    read_artifact(stem, options.format)
 }
 
-fn save_share(share: &DkgShareKey) -> Result<()> {
+// encrypt the plaintext keys in dir in place, skipping keystores and files that are not keys
+fn migrate_keys<T: SecretKey>(
+    dir: &str,
+    prefix: &str,
+    options: &KeyOptions,
+    mut rng: impl RngCore,
+) -> Result<usize> {
+    if !Path::new(dir).exists() {
+        return Ok(0);
+    }
+
+    let mut stems = vec![];
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        let is_key = path
+            .extension()
+            .map_or(false, |ext| ext == "json" || ext == "bin");
+        let is_prefixed = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map_or(false, |stem| stem.starts_with(prefix));
+        if !is_key || !is_prefixed {
+            continue;
+        }
+        let stem = path.with_extension("").to_string_lossy().into_owned();
+        if !stems.contains(&stem) {
+            stems.push(stem);
+        }
+    }
+    stems.sort();
+
+    let mut migrated = 0;
+    for stem in stems.iter() {
+        let json = format!("{stem}.json");
+        if read_keystore(&json).is_some() {
+            continue;
+        }
+        let key: T = match read_artifact(stem, options.format) {
+            Ok(key) => key,
+            Err(_) => {
+                info!("skip {stem}: not a plaintext key");
+                continue;
+            }
+        };
+        let path = save_key(stem, &key, options, &mut rng)?;
+        let bin = format!("{stem}.bin");
+        if Path::new(&bin).exists() {
+            remove_file(&bin)?;
+        }
+        info!("{path} encrypted");
+        migrated += 1;
+    }
+
+    Ok(migrated)
+}
+
+fn save_share(
+    dir: &str,
+    share: &DkgShareKey,
+    options: &KeyOptions,
+    rng: impl RngCore,
+) -> Result<()> {
     let index = share.index();
-    let path = &format!("{DKG_SHARES_DIR}/share_{index}.json");
-    let share_bytes: DkgShareKeySerde = share.into();
-    let serialized = serde_json::to_string(&share_bytes)?;
-    write(path, serialized.as_bytes())?;
+    let path = save_key(&format!("{dir}/share_{index}"), share, options, rng)?;
     info!("dkg secret share for member {index} saved in {path}");
     Ok(())
 }
 
-fn save_gpp(gpp: &DkgGlobalPubParams) -> Result<()> {
-    let gpp_bytes: DkgGlobalPubParamsSerde = gpp.into();
+// gpp.json also records the refresh epoch of its verification keys
+#[derive(Serialize, Deserialize)]
+struct EpochGpp<G> {
+    // gpp.json written before refreshes were introduced has no epoch
+    #[serde(default)]
+    epoch: u64,
+    #[serde(flatten)]
+    gpp: G,
+}
 
-    let path = &format!("{DKG_DIR}/gpk.json");
-    let gpk = gpp_bytes.g2a;
+fn save_gpp(dir: &str, gpp: &DkgGlobalPubParams, epoch: u64) -> Result<()> {
+    let path = &format!("{dir}/gpp.json");
+    let serialized = serde_json::to_string(&EpochGpp { epoch, gpp })?;
+    write(path, serialized.as_bytes())?;
+    info!("global public parameters saved in {path}");
+
+    let path = &format!("{dir}/gpk.json");
+    let gpk: Point2 = gpp.g2a.into();
     let serialized = serde_json::to_string(&gpk).unwrap();
     write(path, serialized.as_bytes())?;
     info!("gpk saved in {path}");
 
-    let path = &format!("{DKG_DIR}/vks.json");
-    let vks = gpp_bytes.verify_keys;
+    let path = &format!("{dir}/vks.json");
+    let vks: Vec<Point> = gpp.verify_keys.iter().map(|vk| vk.into()).collect();
     let serialized = serde_json::to_string(&vks).unwrap();
     write(path, serialized.as_bytes())?;
     info!("verification keys saved in {path}");
@@ -201,22 +622,55 @@ fn save_solidity(name: impl AsRef<str>, solidity: &str) -> Result<()> {
     Ok(())
 }
 
-fn save_proof(proof: &[u8], instance: &[BnScalar], index: usize) -> Result<()> {
-    let path = &format!("{DKG_PROOFS_DIR}/proof_{index}.dat");
+fn save_proof(
+    dir: &str,
+    proof: &[u8],
+    instance: &[BnScalar],
+    index: usize,
+    format: Format,
+) -> Result<()> {
+    let path = &format!("{dir}/proof_{index}.dat");
     write(path, proof)?;
     info!("snark proof for member {index} saved in {path}");
 
-    let path = &format!("{DKG_PROOFS_DIR}/instance_{index}.json");
-    let instance_bytes: Vec<_> = instance
-        .iter()
-        .map(|x| le_bytes_to_hex(x.to_bytes()))
-        .collect();
-    let serialized = serde_json::to_string(&instance_bytes)?;
-    write(path, serialized.as_bytes())?;
+    let path = &format!("{dir}/instance_{index}.{}", format.extension());
+    match format {
+        Format::Json => {
+            let instance_bytes: Vec<_> = instance
+                .iter()
+                .map(|x| le_bytes_to_hex(x.to_bytes()))
+                .collect();
+            let serialized = serde_json::to_string(&instance_bytes)?;
+            write(path, serialized.as_bytes())?;
+        }
+        Format::Binary => write(path, instance.to_vec().to_wire())?,
+    }
     info!("snark instance for member {index} saved in {path}");
     Ok(())
 }
 
+fn read_instance(dir: &str, index: usize, format: Format) -> Result<Vec<BnScalar>> {
+    for f in [format, format.other()] {
+        let path = format!("{dir}/instance_{index}.{}", f.extension());
+        if !Path::new(&path).exists() {
+            continue;
+        }
+        let instance = match f {
+            Format::Json => {
+                let instance_bytes: Vec<String> = serde_json::from_str(&read_to_string(&path)?)?;
+                instance_bytes
+                    .iter()
+                    .map(|e| hex_to_scalar(e))
+                    .collect::<Result<_>>()?
+            }
+            Format::Binary => Vec::<BnScalar>::from_wire(&read(&path)?)?,
+        };
+        return Ok(instance);
+    }
+
+    Err(anyhow!("Snark instance for member {index} not found"))
+}
+
 fn public_keys(dkg_config: &DkgConfig, instance: &[BnScalar]) -> Vec<GkG1> {
     let mut begin = if cfg!(feature = "g2chip") {
         5 * dkg_config.number_of_members() + 14
@@ -235,23 +689,247 @@ fn public_keys(dkg_config: &DkgConfig, instance: &[BnScalar]) -> Vec<GkG1> {
     pks
 }
 
-fn setup(params: &ParamsConfig, skip: bool, split: bool) -> Result<()> {
-    let start = start_timer!(|| format!("kzg load or setup params with degree {}", params.degree));
-    let general_params = load_or_create_params(KZG_PARAMS_DIR, params.degree as usize)?;
-    end_timer!(start);
+// without g2chip, g2a is not in the instance and is published next to the proof
+#[cfg(not(feature = "g2chip"))]
+fn save_g2a(dir: &str, g2a: &BnG2, index: usize) -> Result<()> {
+    let path = &format!("{dir}/g2a_{index}.json");
+    let g2a: Point2 = g2a.into();
+    write(path, serde_json::to_string(&g2a)?)?;
+    info!("g2a for member {index} saved in {path}");
+    Ok(())
+}
 
-    let dkg_config = params.dkg_config()?;
+#[cfg(not(feature = "g2chip"))]
+fn read_g2a(dir: &str, index: usize) -> Result<BnG2> {
+    let path = &format!("{dir}/g2a_{index}.json");
+    let g2a: Point2 = serde_json::from_str(&read_to_string(path)?)?;
+    Ok(BnG2::try_from(&g2a)?)
+}
+
+// instances of all dealers in the order of ppList in the contract
+fn read_all_instances() -> Result<Vec<Vec<BnScalar>>> {
+    let path = &format!("{DKG_DIR}/all_instances.json");
+    let bytes = read_to_string(path)?;
+    let instances_bytes: Vec<Vec<String>> = serde_json::from_str(&bytes)?;
+
+    let mut instances = vec![];
+    for instance in instances_bytes.into_iter() {
+        let s: Vec<_> = instance
+            .iter()
+            .map(|c| hex_to_scalar(c))
+            .collect::<Result<_>>()?;
+        instances.push(s);
+    }
+
+    Ok(instances)
+}
+
+fn read_dkgs_pub(dkg_config: &DkgConfig) -> Result<Vec<DkgMemberPublicParams>> {
+    //decode public parameters from instances
+    let instances = read_all_instances()?;
+
+    #[cfg(feature = "g2chip")]
+    let dkgs_pub = instances
+        .iter()
+        .map(|s| {
+            let (pp, _) = DkgMemberPublicParams::from_instance(dkg_config, s)?;
+            Ok(pp)
+        })
+        .collect::<Result<_>>()?;
+
+    #[cfg(not(feature = "g2chip"))]
+    let dkgs_pub = instances
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            // g2a published by dealer i + 1 next to its proof, checked against the proven ga
+            let g2a = read_g2a(DKG_PROOFS_DIR, i + 1)?;
+            let (pp, _) = DkgMemberPublicParams::from_instance_with_g2a(dkg_config, s, &g2a)?;
+            Ok(pp)
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(dkgs_pub)
+}
+
+// directory of the refresh dealings moving shares and verification keys from epoch to epoch + 1
+fn refresh_dir(epoch: u64) -> String {
+    format!("{REFRESH_DIR}/epoch_{epoch}")
+}
+
+// refresh dealings of an epoch with their proofs, checked against the refresh circuit
+fn read_refresh_dealings(
+    params: &ParamsConfig,
+    dkg_config: &DkgConfig,
+    epoch: u64,
+    format: Format,
+    mut rng: impl RngCore,
+) -> Result<Vec<DkgMemberPublicParams>> {
+    let dir = refresh_dir(epoch);
+    let mut dealers = vec![];
+    let mut proofs = vec![];
+    let mut instances = vec![];
+    for dealer in 1..=dkg_config.number_of_members() {
+        let path = format!("{dir}/proof_{dealer}.dat");
+        if !Path::new(&path).exists() {
+            continue;
+        }
+        dealers.push(dealer);
+        proofs.push(read(&path)?);
+        instances.push(read_instance(&dir, dealer, format)?);
+    }
+    if dealers.is_empty() {
+        return Err(anyhow!("No refresh dealings in {dir}"));
+    }
 
-    let start = start_timer!(|| format!(
-        "kzg load or setup proving keys with degree {}",
-        params.degree
-    ));
-    let pk = load_or_create_pk(
-        dkg_config,
+    let mpks = read_mpks(MEM_PUBLIC_KEYS_PATH)?;
+    let mut dkgs_pub = vec![];
+    for (dealer, instance) in dealers.iter().zip(instances.iter()) {
+        let (pp, pks) = DkgMemberPublicParams::from_refresh_instance(dkg_config, instance)?;
+        if !pks.eq(&mpks) {
+            return Err(anyhow!(
+                "Member public keys do not match for dealer {dealer}"
+            ));
+        }
+        pp.check_refresh(dkg_config, &mut rng)?;
+        dkgs_pub.push(pp);
+    }
+
+    let degree = params.degree(CircuitKind::Refresh)?;
+    let general_params = load_or_create_params(KZG_PARAMS_DIR, degree as usize)?;
+    let vk = load_or_create_vk_of(
+        *dkg_config,
+        CircuitKind::Refresh,
         KZG_PARAMS_DIR,
         &general_params,
-        params.degree as usize,
+        degree as usize,
     )?;
+    let batch: Vec<_> = proofs
+        .iter()
+        .zip(instances.iter())
+        .map(|(proof, instance)| (proof.as_slice(), instance.as_slice()))
+        .collect();
+    verify_batch(general_params.verifier_params(), &vk, &batch)?;
+    info!(
+        "refresh dealings of dealers {:?} verified successfully",
+        dealers
+    );
+
+    Ok(dkgs_pub)
+}
+
+// public parameters of a resharing dealing for the new committee, read like a NIDKG dealing
+fn read_reshare_public_params(
+    new_config: &DkgConfig,
+    index: usize,
+    format: Format,
+) -> Result<(Vec<u8>, Vec<BnScalar>, DkgMemberPublicParams)> {
+    let proof = read(format!("{RESHARE_PROOFS_DIR}/proof_{index}.dat"))?;
+    let instance = read_instance(RESHARE_PROOFS_DIR, index, format)?;
+
+    #[cfg(feature = "g2chip")]
+    let (pp, pks) = DkgMemberPublicParams::from_instance(new_config, &instance)?;
+    #[cfg(not(feature = "g2chip"))]
+    let (pp, pks) = {
+        let g2a = read_g2a(RESHARE_PROOFS_DIR, index)?;
+        DkgMemberPublicParams::from_instance_with_g2a(new_config, &instance, &g2a)?
+    };
+    if !pks.eq(&read_mpks(RESHARE_PUBLIC_KEYS_PATH)?) {
+        return Err(anyhow!(
+            "Member public keys do not match for dealer {index}"
+        ));
+    }
+
+    Ok((proof, instance, pp))
+}
+
+fn read_member(file: Option<String>, options: &KeyOptions) -> Result<MemberKey> {
+    let stem = file
+        .map(|f| format!("{MEMBERS_DIR}/{f}"))
+        .ok_or_else(|| anyhow!("File path not available"))?;
+    read_key(&stem, options)
+}
+
+fn hex_to_scalar(s: &str) -> Result<BnScalar> {
+    Option::from(BnScalar::from_bytes(&hex_to_le_bytes(s)?))
+        .ok_or_else(|| anyhow!("Failed to deserialise Bn256 scalar"))
+}
+
+fn read_mpks(path: &str) -> Result<Vec<GkG1>> {
+    let bytes = read_to_string(path)?;
+    let mpks_bytes: Vec<Point> = serde_json::from_str(&bytes)?;
+    let mpks = mpks_bytes
+        .iter()
+        .map(|pk| pk.try_into())
+        .collect::<Result<_, _>>()?;
+    Ok(mpks)
+}
+
+fn read_vks() -> Result<Vec<BnG1>> {
+    let path = format!("{DKG_DIR}/vks.json");
+    let bytes = read_to_string(path)?;
+    let vks_bytes: Vec<Point> = serde_json::from_str(&bytes)?;
+    let vks = vks_bytes
+        .iter()
+        .map(|vk| vk.try_into())
+        .collect::<Result<_, _>>()?;
+    Ok(vks)
+}
+
+fn read_gpp(dir: &str) -> Result<(DkgGlobalPubParams, u64)> {
+    let path = format!("{dir}/gpp.json");
+    let bytes = read_to_string(path)?;
+    let EpochGpp { epoch, gpp } = serde_json::from_str(&bytes)?;
+    Ok((gpp, epoch))
+}
+
+fn read_gpk() -> Result<BnG2> {
+    let path = format!("{DKG_DIR}/gpk.json");
+    let bytes = read_to_string(path)?;
+    let gpk_bytes: Point2 = serde_json::from_str(&bytes)?;
+    Ok((&gpk_bytes).try_into()?)
+}
+
+fn save_complaint(complaint: &Complaint) -> Result<()> {
+    let (accuser, dealer) = (complaint.accuser, complaint.dealer);
+    let path = &format!("{DKG_COMPLAINTS_DIR}/complaint_{accuser}_{dealer}.json");
+    let serialized = serde_json::to_string(complaint)?;
+    write(path, serialized.as_bytes())?;
+    info!("complaint of member {accuser} against dealer {dealer} saved in {path}");
+    Ok(())
+}
+
+fn read_complaints() -> Result<Vec<Complaint>> {
+    let mut complaints = vec![];
+    for entry in read_dir(DKG_COMPLAINTS_DIR)? {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+        let bytes = read_to_string(path)?;
+        complaints.push(serde_json::from_str(&bytes)?);
+    }
+
+    Ok(complaints)
+}
+
+fn log_qualified(qualified: &QualifiedSet) {
+    info!("qualified dealers: {:?}", qualified.qualified);
+    for excluded in qualified.excluded.iter() {
+        info!("dealer {} excluded: {:?}", excluded.dealer, excluded.reason);
+    }
+}
+
+fn setup(params: &ParamsConfig, skip: bool, split: bool) -> Result<()> {
+    let degree = params.degree(CircuitKind::Dkg)?;
+    let start = start_timer!(|| format!("kzg load or setup params with degree {}", degree));
+    let general_params = load_or_create_params(KZG_PARAMS_DIR, degree as usize)?;
+    end_timer!(start);
+
+    let dkg_config = params.dkg_config()?;
+
+    let start = start_timer!(|| format!("kzg load or setup proving keys with degree {}", degree));
+    let pk = load_or_create_pk(dkg_config, KZG_PARAMS_DIR, &general_params, degree as usize)?;
     let vk = pk.get_vk();
     end_timer!(start);
 
@@ -261,7 +939,7 @@ fn setup(params: &ParamsConfig, skip: bool, split: bool) -> Result<()> {
         if split {
             let start = start_timer!(|| format!(
                 "create verifier contract and verifying key contract for ({}, {}, {})",
-                params.threshold, params.number_of_members, params.degree
+                params.threshold, params.number_of_members, degree
             ));
             let generator = SolidityGenerator::new(&general_params, vk, Bdfg21, num_instances);
             let (verifier_solidity, vk_solidity) = generator.render_separately().unwrap();
@@ -270,12 +948,12 @@ fn setup(params: &ParamsConfig, skip: bool, split: bool) -> Result<()> {
             let contract_name = if cfg!(feature = "g2chip") {
                 format!(
                     "Halo2VerifyingKey-{}-{}-{}-g2.sol",
-                    params.threshold, params.number_of_members, params.degree,
+                    params.threshold, params.number_of_members, degree,
                 )
             } else {
                 format!(
                     "Halo2VerifyingKey-{}-{}-{}.sol",
-                    params.threshold, params.number_of_members, params.degree,
+                    params.threshold, params.number_of_members, degree,
                 )
             };
 
@@ -284,19 +962,19 @@ fn setup(params: &ParamsConfig, skip: bool, split: bool) -> Result<()> {
         } else {
             let start = start_timer!(|| format!(
                 "create solidity contracts for ({},{},{})",
-                params.threshold, params.number_of_members, params.degree
+                params.threshold, params.number_of_members, degree
             ));
             let generator = SolidityGenerator::new(&general_params, vk, Bdfg21, num_instances);
             let verifier_solidity = generator.render()?;
             let contract_name = if cfg!(feature = "g2chip") {
                 format!(
                     "Halo2Verifier-{}-{}-{}-g2.sol",
-                    params.threshold, params.number_of_members, params.degree,
+                    params.threshold, params.number_of_members, degree,
                 )
             } else {
                 format!(
                     "Halo2Verifier-{}-{}-{}.sol",
-                    params.threshold, params.number_of_members, params.degree,
+                    params.threshold, params.number_of_members, degree,
                 )
             };
 
@@ -318,6 +996,10 @@ fn main() -> Result<()> {
     create_dir_all(DKG_SECRETS_DIR)?;
     create_dir_all(DKG_PROOFS_DIR)?;
     create_dir_all(DKG_SHARES_DIR)?;
+    create_dir_all(DKG_COMPLAINTS_DIR)?;
+    create_dir_all(REFRESH_DIR)?;
+    create_dir_all(RESHARE_PROOFS_DIR)?;
+    create_dir_all(RESHARE_SHARES_DIR)?;
     create_dir_all(CONTRACT_DIR)?;
     create_dir_all(RANDOM_DIR)?;
 
@@ -333,21 +1015,24 @@ fn main() -> Result<()> {
         })
     };
 
-    let config_keys = ["THRESHOLD", "NUMBER_OF_MEMBERS", "DEGREE"];
-    let default_values: (u32, u32, u32) = (3, 5, 18);
+    let config_keys = ["THRESHOLD", "NUMBER_OF_MEMBERS"];
+    let default_values: (u32, u32, Option<u32>) = (3, 5, Some(18));
     let (threshold, number_of_members, degree) = if is_any_var_missing_or_invalid(&config_keys) {
         info!("One or more env variables are missing or invalid. Using default config");
         default_values
     } else {
-        (
-            env::var(config_keys[0]).unwrap().parse::<u32>().unwrap(),
-            env::var(config_keys[1]).unwrap().parse::<u32>().unwrap(),
-            env::var(config_keys[2]).unwrap().parse::<u32>().unwrap(),
-        )
+        let threshold = env::var(config_keys[0]).unwrap().parse::<u32>().unwrap();
+        let number_of_members = env::var(config_keys[1]).unwrap().parse::<u32>().unwrap();
+        // without DEGREE the circuit is measured when the degree is needed
+        let degree = env::var("DEGREE")
+            .ok()
+            .and_then(|val| val.parse::<u32>().ok());
+
+        (threshold, number_of_members, degree)
     };
 
     info!(
-        "(threshold, number_of_members, degree) = ({}, {}, {})",
+        "(threshold, number_of_members, degree) = ({}, {}, {:?})",
         threshold, number_of_members, degree
     );
 
@@ -359,6 +1044,12 @@ fn main() -> Result<()> {
     let dkg_config = params.dkg_config()?;
 
     let cli = Cli::parse();
+    let format = cli.format;
+    let key_options = KeyOptions {
+        format,
+        kdf: cli.password.kdf(),
+        password: cli.password,
+    };
     match cli.command {
         Commands::Mock(mock) => {
             if mock.members {
@@ -389,13 +1080,11 @@ fn main() -> Result<()> {
         Commands::Keygen { file } => {
             let member = MemberKey::random(&mut rng);
             let mpk = member.public_key();
-            let member_bytes: MemberKeySerde = member.into();
 
-            let path = file
-                .map(|f| format!("{MEMBERS_DIR}/{f}.json"))
+            let stem = file
+                .map(|f| format!("{MEMBERS_DIR}/{f}"))
                 .ok_or_else(|| anyhow!("File path not available"))?;
-            let member_seralised = serde_json::to_string(&member_bytes)?;
-            write(&path, &member_seralised)?;
+            let path = save_key(&stem, &member, &key_options, &mut rng)?;
             info!(
                 "member secret key and public key generated and saved in {}",
                 path
@@ -412,41 +1101,45 @@ fn main() -> Result<()> {
                         return Err(anyhow!("Invalid member index"));
                     }
                     // read all member public keys
-                    let bytes = read_to_string(MEM_PUBLIC_KEYS_PATH)?;
-                    let mpks_bytes: Vec<Point> = serde_json::from_str(&bytes)?;
-                    let mpks: Vec<GkG1> = mpks_bytes.into_iter().map(|pk| pk.into()).collect();
+                    let mpks = read_mpks(MEM_PUBLIC_KEYS_PATH)?;
 
                     let dkg = DkgMemberParams::new(dkg_config, mpks, &mut rng)?;
                     let circuit = dkg.circuit(&mut rng);
                     let instance = dkg.instance();
 
-                    let start = start_timer!(|| format!(
-                        "kzg load or setup params with degree {}",
-                        params.degree
-                    ));
+                    let degree = params.degree(CircuitKind::Dkg)?;
+                    let start =
+                        start_timer!(|| format!("kzg load or setup params with degree {}", degree));
                     let params_dir = "./kzg_params";
-                    let general_params = load_or_create_params(params_dir, params.degree as usize)?;
+                    let general_params = load_or_create_params(params_dir, degree as usize)?;
                     end_timer!(start);
 
                     let start = start_timer!(|| format!(
                         "kzg load or setup proving keys with degree {}",
-                        params.degree
+                        degree
                     ));
                     let pk = load_or_create_pk(
                         dkg_config,
                         params_dir,
                         &general_params,
-                        params.degree as usize,
+                        degree as usize,
                     )?;
                     end_timer!(start);
 
                     let start = start_timer!(|| format!("create and verify proof"));
-                    let proof =
-                        create_proof_checked(&general_params, &pk, circuit, &instance[0], &mut rng);
+                    let proof = create_proof_checked(
+                        &general_params,
+                        &pk,
+                        circuit,
+                        &instance[0],
+                        &mut rng,
+                    )?;
                     end_timer!(start);
                     info!("size of proof {:?}", proof.len());
 
-                    save_proof(&proof, &instance[0], index)?;
+                    save_proof(DKG_PROOFS_DIR, &proof, &instance[0], index, format)?;
+                    #[cfg(not(feature = "g2chip"))]
+                    save_g2a(DKG_PROOFS_DIR, &dkg.member_public_params().g2a, index)?;
                 }
                 DkgCommands::Verify { index } => {
                     if index < 1 || index > dkg_config.number_of_members() {
@@ -457,24 +1150,13 @@ fn main() -> Result<()> {
                     let proof = read(proof_path)?;
 
                     // read instance
-                    let instance_path = format!("{DKG_PROOFS_DIR}/instance_{index}.json");
-                    let bytes = read_to_string(instance_path)?;
-                    let instance_bytes: Vec<String> = serde_json::from_str(&bytes)?;
-                    let instance: Vec<BnScalar> = instance_bytes
-                        .iter()
-                        .map(|e| {
-                            BnScalar::from_bytes(&hex_to_le_bytes(e))
-                                .expect("Failed to deserialise Bn256 scalar")
-                        })
-                        .collect();
+                    let instance = read_instance(DKG_PROOFS_DIR, index, format)?;
 
                     {
                         // check if public keys in instance are correct
                         // read all member public keys
                         let start = start_timer!(|| "verify member public keys in instance");
-                        let bytes = read_to_string(MEM_PUBLIC_KEYS_PATH)?;
-                        let mpks_bytes: Vec<Point> = serde_json::from_str(&bytes)?;
-                        let mpks: Vec<GkG1> = mpks_bytes.into_iter().map(|pk| pk.into()).collect();
+                        let mpks = read_mpks(MEM_PUBLIC_KEYS_PATH)?;
                         let pks = public_keys(&dkg_config, &instance);
 
                         if !pks.eq(&mpks) {
@@ -483,112 +1165,464 @@ fn main() -> Result<()> {
                         end_timer!(start);
                     }
 
-                    let start = start_timer!(|| format!(
-                        "kzg load or setup params with degree {}",
-                        params.degree
-                    ));
+                    // reject dealings whose public shares are not of degree t-1 before the snark
+                    #[cfg(feature = "g2chip")]
+                    let (pp, _) = DkgMemberPublicParams::from_instance(&dkg_config, &instance)?;
+                    #[cfg(not(feature = "g2chip"))]
+                    let (pp, _) = {
+                        let g2a = read_g2a(DKG_PROOFS_DIR, index)?;
+                        DkgMemberPublicParams::from_instance_with_g2a(&dkg_config, &instance, &g2a)?
+                    };
+                    pp.check_low_degree(&dkg_config, &mut rng)?;
+                    info!("public shares of member {index} passed the low degree check");
+
+                    let degree = params.degree(CircuitKind::Dkg)?;
+                    let start =
+                        start_timer!(|| format!("kzg load or setup params with degree {}", degree));
                     let params_dir = "./kzg_params";
-                    let general_params = load_or_create_params(params_dir, params.degree as usize)?;
+                    let general_params = load_or_create_params(params_dir, degree as usize)?;
                     end_timer!(start);
 
                     let start = start_timer!(|| format!(
                         "kzg load or setup verifying keys with degree {}",
-                        params.degree
+                        degree
                     ));
                     let vk = load_or_create_vk(
                         dkg_config,
                         params_dir,
                         &general_params,
-                        params.degree as usize,
+                        degree as usize,
                     )?;
                     end_timer!(start);
 
-                    verify_single(general_params.verifier_params(), &vk, &proof, &instance);
+                    verify_single(general_params.verifier_params(), &vk, &proof, &instance)?;
+                    info!("dkg proof of member {index} verified successfully");
                 }
-                DkgCommands::Derive { index, file } => {
-                    #[cfg(feature = "g2chip")]
-                    let dkgs_pub = {
-                        //decode public parameters from instances
-                        let path = &format!("{DKG_DIR}/all_instances.json");
-                        let bytes = read_to_string(path)?;
-                        let instances_bytes: Vec<Vec<String>> = serde_json::from_str(&bytes)?;
-
-                        let mut instances = vec![];
-                        for instance in instances_bytes.into_iter() {
-                            let s: Vec<_> = instance
-                                .iter()
-                                .map(|c| {
-                                    BnScalar::from_bytes(&hex_to_le_bytes(c))
-                                        .expect("Failed to deserialise Bn256 scalar")
-                                })
-                                .collect();
-                            instances.push(s);
+                DkgCommands::VerifyAll => {
+                    let instances = read_all_instances()?;
+                    let proofs = (1..=instances.len())
+                        .map(|index| Ok(read(format!("{DKG_PROOFS_DIR}/proof_{index}.dat"))?))
+                        .collect::<Result<Vec<_>>>()?;
+
+                    let mpks = read_mpks(MEM_PUBLIC_KEYS_PATH)?;
+                    for (i, instance) in instances.iter().enumerate() {
+                        if instance.len() != dkg_config.instance_size() {
+                            return Err(anyhow!("Malformed instance for dealer {}", i + 1));
                         }
+                        if !public_keys(&dkg_config, instance).eq(&mpks) {
+                            return Err(anyhow!(
+                                "Member public keys do not match for dealer {}",
+                                i + 1
+                            ));
+                        }
+                    }
 
-                        let dkgs_pub: Vec<_> = instances
-                            .iter()
-                            .map(|s| {
-                                let (pp, _) = DkgMemberPublicParams::from_instance(&dkg_config, s);
-                                pp
-                            })
-                            .collect();
-
-                        dkgs_pub
-                    };
+                    let params_dir = "./kzg_params";
+                    let degree = params.degree(CircuitKind::Dkg)?;
+                    let general_params = load_or_create_params(params_dir, degree as usize)?;
+                    let vk = load_or_create_vk(
+                        dkg_config,
+                        params_dir,
+                        &general_params,
+                        degree as usize,
+                    )?;
 
-                    #[cfg(not(feature = "g2chip"))]
-                    let dkgs_pub = {
-                        // todo: get public parameters from instances (g2a is not available in this case)
-                        let path = &format!("{DKG_DIR}/dkgs_public.json");
-                        let bytes = read_to_string(path)?;
-                        let dkgs_pub_bytes: Vec<DkgMemberPublicParamsSerde> =
-                            serde_json::from_str(&bytes)?;
-                        let dkgs_pub: Vec<DkgMemberPublicParams> =
-                            dkgs_pub_bytes.into_iter().map(|d| d.into()).collect();
-
-                        dkgs_pub
+                    let batch: Vec<_> = proofs
+                        .iter()
+                        .zip(instances.iter())
+                        .map(|(proof, instance)| (proof.as_slice(), instance.as_slice()))
+                        .collect();
+                    verify_batch(general_params.verifier_params(), &vk, &batch)?;
+                    info!(
+                        "dkg proofs of {} dealers verified successfully",
+                        batch.len()
+                    );
+                }
+                DkgCommands::Derive {
+                    index,
+                    file,
+                    min_dealers,
+                } => {
+                    let policy = match min_dealers {
+                        Some(m) => QualifiedPolicy::new(&dkg_config, m)?,
+                        None => QualifiedPolicy::all(&dkg_config),
                     };
 
-                    let dkgs_pub_ref: Vec<_> = dkgs_pub.iter().map(|d| d).collect();
-                    let gpp = dkg_global_public_params(&dkgs_pub_ref);
-                    save_gpp(&gpp)?;
+                    let dkgs_pub = read_dkgs_pub(&dkg_config)?;
+                    let dkgs_pub_ref: Vec<_> = dkgs_pub.iter().collect();
+                    let mpks = read_mpks(MEM_PUBLIC_KEYS_PATH)?;
+                    let complaints = read_complaints()?;
 
                     if let Some(index) = index {
                         if index < 1 || index > dkg_config.number_of_members() {
                             return Err(anyhow!("Invalid member index"));
                         }
 
-                        let path = file
-                            .map(|f| format!("{MEMBERS_DIR}/{f}.json"))
-                            .ok_or_else(|| anyhow!("File path not available"))?;
-                        let bytes = read_to_string(path)?;
-                        let member_bytes: MemberKeySerde = serde_json::from_str(&bytes)?;
-                        let member: MemberKey = member_bytes.into();
+                        let member = read_member(file, &key_options)?;
+
+                        let derivation = member.derive_qualified(
+                            &dkg_config,
+                            index,
+                            &mpks,
+                            &dkgs_pub_ref,
+                            &complaints,
+                            &policy,
+                            &mut rng,
+                        )?;
+                        // publish the new complaints so that other members exclude the same dealers
+                        for complaint in derivation.complaints.iter() {
+                            save_complaint(complaint)?;
+                        }
+                        log_qualified(&derivation.qualified);
+
+                        save_gpp(DKG_DIR, &derivation.global_params, 0)?;
+                        save_share(
+                            DKG_SHARES_DIR,
+                            &derivation.share_key,
+                            &key_options,
+                            &mut rng,
+                        )?;
+                    } else {
+                        let qualified = qualified_dealers(
+                            &dkg_config,
+                            &mpks,
+                            &dkgs_pub_ref,
+                            &complaints,
+                            &policy,
+                        )?;
+                        log_qualified(&qualified);
+
+                        let gpp = dkg_global_public_params(
+                            &dkg_config,
+                            &qualified.select(&dkgs_pub_ref),
+                        )?;
+                        save_gpp(DKG_DIR, &gpp, 0)?;
+                    }
+                }
+                DkgCommands::Complain { index, file } => {
+                    if index < 1 || index > dkg_config.number_of_members() {
+                        return Err(anyhow!("Invalid member index"));
+                    }
+
+                    let member = read_member(file, &key_options)?;
+                    let dkgs_pub = read_dkgs_pub(&dkg_config)?;
+                    let dkgs_pub_ref: Vec<_> = dkgs_pub.iter().collect();
+
+                    let complaints =
+                        member.check_shares(&dkg_config, index, &dkgs_pub_ref, &mut rng)?;
+                    if complaints.is_empty() {
+                        info!("all shares for member {index} are valid");
+                    }
+                    for complaint in complaints.iter() {
+                        save_complaint(complaint)?;
+                    }
+                }
+                DkgCommands::VerifyComplaint { accuser, dealer } => {
+                    let path = &format!("{DKG_COMPLAINTS_DIR}/complaint_{accuser}_{dealer}.json");
+                    let bytes = read_to_string(path)?;
+                    let complaint: Complaint = serde_json::from_str(&bytes)?;
+
+                    let dkgs_pub = read_dkgs_pub(&dkg_config)?;
+                    if dealer < 1 || dealer > dkgs_pub.len() {
+                        return Err(anyhow!("Invalid dealer index"));
+                    }
+                    let mpks = read_mpks(MEM_PUBLIC_KEYS_PATH)?;
+
+                    complaint.verify(&dkg_config, &mpks, &dkgs_pub[dealer - 1])?;
+                    info!("complaint of member {accuser} against dealer {dealer} verified successfully");
+                }
+            }
+        }
+        Commands::Refresh(refresh) => match refresh.command {
+            RefreshCommands::Prove { index } => {
+                if index < 1 || index > dkg_config.number_of_members() {
+                    return Err(anyhow!("Invalid member index"));
+                }
+                let mpks = read_mpks(MEM_PUBLIC_KEYS_PATH)?;
+                // the dealing refreshes the verification keys at their current epoch
+                let (_, epoch) = read_gpp(DKG_DIR)?;
+                let dir = refresh_dir(epoch);
+                create_dir_all(&dir)?;
+
+                let dkg = DkgMemberParams::new_refresh(dkg_config, mpks, &mut rng)?;
+                let circuit = dkg.refresh_circuit(&mut rng);
+                let instance = dkg.refresh_instance();
+
+                let degree = params.degree(CircuitKind::Refresh)?;
+                let start =
+                    start_timer!(|| format!("kzg load or setup params with degree {}", degree));
+                let general_params = load_or_create_params(KZG_PARAMS_DIR, degree as usize)?;
+                end_timer!(start);
+
+                let start = start_timer!(|| format!(
+                    "kzg load or setup refresh proving keys with degree {}",
+                    degree
+                ));
+                let pk = load_or_create_pk_of(
+                    dkg_config,
+                    CircuitKind::Refresh,
+                    KZG_PARAMS_DIR,
+                    &general_params,
+                    degree as usize,
+                )?;
+                end_timer!(start);
+
+                let start = start_timer!(|| "create and verify refresh proof");
+                let proof =
+                    create_proof_checked(&general_params, &pk, circuit, &instance[0], &mut rng)?;
+                end_timer!(start);
+                info!("size of proof {:?}", proof.len());
+
+                save_proof(&dir, &proof, &instance[0], index, format)?;
+            }
+            RefreshCommands::Verify { index } => {
+                if index < 1 || index > dkg_config.number_of_members() {
+                    return Err(anyhow!("Invalid member index"));
+                }
+
+                let (_, epoch) = read_gpp(DKG_DIR)?;
+                let dir = refresh_dir(epoch);
+                let proof = read(format!("{dir}/proof_{index}.dat"))?;
+                let instance = read_instance(&dir, index, format)?;
+
+                let (pp, pks) =
+                    DkgMemberPublicParams::from_refresh_instance(&dkg_config, &instance)?;
+                if !pks.eq(&read_mpks(MEM_PUBLIC_KEYS_PATH)?) {
+                    return Err(anyhow!("Member public keys do not match"));
+                }
+                pp.check_refresh(&dkg_config, &mut rng)?;
+
+                let degree = params.degree(CircuitKind::Refresh)?;
+                let general_params = load_or_create_params(KZG_PARAMS_DIR, degree as usize)?;
+                let vk = load_or_create_vk_of(
+                    dkg_config,
+                    CircuitKind::Refresh,
+                    KZG_PARAMS_DIR,
+                    &general_params,
+                    degree as usize,
+                )?;
+
+                verify_single(general_params.verifier_params(), &vk, &proof, &instance)?;
+                info!("refresh proof of member {index} verified successfully");
+            }
+            RefreshCommands::Derive { index, file } => {
+                // the share and the global public parameters are each refreshed once per epoch,
+                // by the member and by the public respectively, with the dealings of that epoch
+                let (gpp, gpp_epoch) = read_gpp(DKG_DIR)?;
+                if let Some(index) = index {
+                    if index < 1 || index > dkg_config.number_of_members() {
+                        return Err(anyhow!("Invalid member index"));
+                    }
+
+                    let member = read_member(file, &key_options)?;
+                    let share: DkgShareKey =
+                        read_key(&format!("{DKG_SHARES_DIR}/share_{index}"), &key_options)?;
+                    let epoch = share.epoch();
+                    let dkgs_pub =
+                        read_refresh_dealings(&params, &dkg_config, epoch, format, &mut rng)?;
+                    let dkgs_pub_ref: Vec<_> = dkgs_pub.iter().collect();
+
+                    // gpp.json may already have been refreshed with the same dealings
+                    let vks = if gpp_epoch == epoch {
+                        refresh_global_public_params(&dkg_config, &gpp, &dkgs_pub_ref, &mut rng)?
+                            .verify_keys
+                    } else if gpp_epoch == epoch + 1 {
+                        gpp.verify_keys
+                    } else {
+                        return Err(anyhow!(
+                            "Share of member {index} is at epoch {epoch}, gpp at epoch {gpp_epoch}"
+                        ));
+                    };
+
+                    let share =
+                        member.refresh_share_key(&dkg_config, &share, &dkgs_pub_ref, &mut rng)?;
+                    share.verify(&dkg_config, &vks)?;
+                    save_share(DKG_SHARES_DIR, &share, &key_options, &mut rng)?;
+                } else {
+                    let dkgs_pub =
+                        read_refresh_dealings(&params, &dkg_config, gpp_epoch, format, &mut rng)?;
+                    let dkgs_pub_ref: Vec<_> = dkgs_pub.iter().collect();
+
+                    let gpp =
+                        refresh_global_public_params(&dkg_config, &gpp, &dkgs_pub_ref, &mut rng)?;
+                    save_gpp(DKG_DIR, &gpp, gpp_epoch + 1)?;
+                }
+            }
+        },
+        Commands::Reshare(reshare) => {
+            // the old committee is given by THRESHOLD and NUMBER_OF_MEMBERS; resharing dealings
+            // are NIDKG dealings for the new committee, proven with the keys of its circuit
+            let new_params = ParamsConfig {
+                threshold: reshare.new_threshold,
+                number_of_members: reshare.new_members,
+                degree: reshare.new_degree,
+            };
+            let new_config = new_params.dkg_config()?;
+
+            match reshare.command {
+                ReshareCommands::Prove { index } => {
+                    if index < 1 || index > dkg_config.number_of_members() {
+                        return Err(anyhow!("Invalid member index"));
+                    }
+
+                    let share: DkgShareKey =
+                        read_key(&format!("{DKG_SHARES_DIR}/share_{index}"), &key_options)?;
+                    let mpks = read_mpks(RESHARE_PUBLIC_KEYS_PATH)?;
+
+                    let dkg = DkgMemberParams::new_reshare(new_config, &share, mpks, &mut rng)?;
+                    let circuit = dkg.circuit(&mut rng);
+                    let instance = dkg.instance();
+
+                    let degree = new_params.degree(CircuitKind::Dkg)?;
+                    let general_params = load_or_create_params(KZG_PARAMS_DIR, degree as usize)?;
+                    let pk = load_or_create_pk(
+                        new_config,
+                        KZG_PARAMS_DIR,
+                        &general_params,
+                        degree as usize,
+                    )?;
+
+                    let start = start_timer!(|| "create and verify resharing proof");
+                    let proof = create_proof_checked(
+                        &general_params,
+                        &pk,
+                        circuit,
+                        &instance[0],
+                        &mut rng,
+                    )?;
+                    end_timer!(start);
+                    info!("size of proof {:?}", proof.len());
+
+                    save_proof(RESHARE_PROOFS_DIR, &proof, &instance[0], index, format)?;
+                    #[cfg(not(feature = "g2chip"))]
+                    save_g2a(RESHARE_PROOFS_DIR, &dkg.member_public_params().g2a, index)?;
+                }
+                ReshareCommands::Verify { index } => {
+                    if index < 1 || index > dkg_config.number_of_members() {
+                        return Err(anyhow!("Invalid member index"));
+                    }
+
+                    let (proof, instance, pp) =
+                        read_reshare_public_params(&new_config, index, format)?;
+
+                    // the dealt secret has to be the share behind the dealer's old verification key
+                    let (old_gpp, _) = read_gpp(DKG_DIR)?;
+                    if old_gpp.verify_keys.len() != dkg_config.number_of_members() {
+                        return Err(anyhow!("Invalid verification keys of the old committee"));
+                    }
+                    pp.check_reshare(&new_config, &old_gpp.verify_keys[index - 1])?;
+
+                    let degree = new_params.degree(CircuitKind::Dkg)?;
+                    let general_params = load_or_create_params(KZG_PARAMS_DIR, degree as usize)?;
+                    let vk = load_or_create_vk(
+                        new_config,
+                        KZG_PARAMS_DIR,
+                        &general_params,
+                        degree as usize,
+                    )?;
+
+                    verify_single(general_params.verifier_params(), &vk, &proof, &instance)?;
+                    info!("resharing proof of member {index} verified successfully");
+                }
+                ReshareCommands::Derive { index, file } => {
+                    let mut dealers = vec![];
+                    let mut proofs = vec![];
+                    let mut instances = vec![];
+                    let mut dkgs_pub = vec![];
+                    for dealer in 1..=dkg_config.number_of_members() {
+                        if !Path::new(&format!("{RESHARE_PROOFS_DIR}/proof_{dealer}.dat")).exists()
+                        {
+                            continue;
+                        }
+                        let (proof, instance, pp) =
+                            read_reshare_public_params(&new_config, dealer, format)?;
+                        dealers.push(dealer);
+                        proofs.push(proof);
+                        instances.push(instance);
+                        dkgs_pub.push(pp);
+                    }
+                    if dealers.is_empty() {
+                        return Err(anyhow!("No resharing dealings in {RESHARE_PROOFS_DIR}"));
+                    }
+
+                    let degree = new_params.degree(CircuitKind::Dkg)?;
+                    let general_params = load_or_create_params(KZG_PARAMS_DIR, degree as usize)?;
+                    let vk = load_or_create_vk(
+                        new_config,
+                        KZG_PARAMS_DIR,
+                        &general_params,
+                        degree as usize,
+                    )?;
+                    let batch: Vec<_> = proofs
+                        .iter()
+                        .zip(instances.iter())
+                        .map(|(proof, instance)| (proof.as_slice(), instance.as_slice()))
+                        .collect();
+                    verify_batch(general_params.verifier_params(), &vk, &batch)?;
+                    info!(
+                        "resharing dealings of dealers {:?} verified successfully",
+                        dealers
+                    );
 
-                        let share = member.dkg_share_key(&dkg_config, index, &dkgs_pub_ref)?;
-                        share.verify(&dkg_config, &gpp.verify_keys)?;
+                    let dealings: Vec<_> = dealers
+                        .iter()
+                        .zip(dkgs_pub.iter())
+                        .map(|(&dealer, pp)| ReshareDealing {
+                            dealer,
+                            public_params: pp,
+                        })
+                        .collect();
+                    let (old_gpp, _) = read_gpp(DKG_DIR)?;
 
-                        save_share(&share)?;
+                    let gpp = reshare_global_public_params(
+                        &dkg_config,
+                        &old_gpp,
+                        &new_config,
+                        &dealings,
+                    )?;
+                    save_gpp(RESHARE_DIR, &gpp, 0)?;
+
+                    if let Some(index) = index {
+                        if index < 1 || index > new_config.number_of_members() {
+                            return Err(anyhow!("Invalid member index"));
+                        }
+
+                        let member = read_member(file, &key_options)?;
+                        let share = member.reshare_share_key(
+                            &dkg_config,
+                            &old_gpp,
+                            &new_config,
+                            index,
+                            &dealings,
+                        )?;
+                        save_share(RESHARE_SHARES_DIR, &share, &key_options, &mut rng)?;
                     }
                 }
             }
         }
         Commands::Rand(rand) => {
+            let domain = cli.domain.domain()?;
+            info!("evaluation domain \"{}\"", domain.prefix());
+
             match rand.command {
-                RandCommands::Eval { index, input } => {
+                RandCommands::Eval {
+                    index,
+                    input,
+                    deterministic,
+                } => {
                     if index < 1 || index > dkg_config.number_of_members() {
                         return Err(anyhow!("Invalid member index"));
                     }
 
-                    let path = &format!("{DKG_SHARES_DIR}/share_{index}.json");
-                    let bytes = read_to_string(path)?;
-                    let share_bytes: DkgShareKeySerde = serde_json::from_str(&bytes)?;
-                    let share: DkgShareKey = share_bytes.into();
-                    let sigma = share.evaluate(input.as_bytes(), &mut rng);
-                    let sigma_bytes: PartialEvalSerde = sigma.into();
-                    let serialised = serde_json::to_string(&sigma_bytes)?;
-                    let path = &format!("{RANDOM_DIR}/eval_{index}.json");
-                    write(path, serialised.as_bytes())?;
+                    let share: DkgShareKey =
+                        read_key(&format!("{DKG_SHARES_DIR}/share_{index}"), &key_options)?;
+                    let sigma = if deterministic {
+                        share.evaluate_deterministic(&domain, input.as_bytes())
+                    } else {
+                        share.evaluate(&domain, input.as_bytes(), &mut rng)
+                    };
+                    let path =
+                        save_artifact(&format!("{RANDOM_DIR}/eval_{index}"), &sigma, format)?;
                     info!("partial eval for member {index} on input \"{input}\" generated and saved in {path}");
                 }
                 RandCommands::Verify { index, input } => {
@@ -596,91 +1630,106 @@ fn main() -> Result<()> {
                         return Err(anyhow!("Invalid member index"));
                     }
 
-                    let path = &format!("{RANDOM_DIR}/eval_{index}.json");
-                    let bytes = read_to_string(path)?;
-                    let sigma_bytes: PartialEvalSerde = serde_json::from_str(&bytes)?;
-                    let sigma: PartialEval = sigma_bytes.into();
+                    let sigma: PartialEval =
+                        read_artifact(&format!("{RANDOM_DIR}/eval_{index}"), format)?;
 
-                    let path = &format!("{DKG_DIR}/vks.json");
-                    let bytes = read_to_string(path)?;
-                    let vks_bytes: Vec<Point> = serde_json::from_str(&bytes)?;
-                    let vks: Vec<BnG1> = vks_bytes.iter().map(|vk| vk.into()).collect();
+                    let vks = read_vks()?;
+                    check_verify_keys(&dkg_config, &vks, &mut rng)?;
 
-                    sigma.verify(&dkg_config, input.as_bytes(), &vks[index - 1])?;
+                    sigma.verify(&dkg_config, &domain, input.as_bytes(), &vks[index - 1])?;
                     info!("partial eval for member {index} on input \"{input}\" verified successfully");
                 }
                 RandCommands::Combine { input, skip } => {
-                    let path = format!("{RANDOM_DIR}/evals.json");
-                    let bytes = read_to_string(path)?;
-                    let evals_bytes: Vec<PartialEvalSerde> = serde_json::from_str(&bytes)?;
                     let evals: Vec<PartialEval> =
-                        evals_bytes.into_iter().map(|e| e.into()).collect();
+                        read_artifact(&format!("{RANDOM_DIR}/evals"), format)?;
 
                     // read dkg global public parameters
-                    let path = format!("{DKG_DIR}/gpk.json");
-                    let bytes = read_to_string(path)?;
-                    let gpk_bytes: Point2 = serde_json::from_str(&bytes)?;
-                    let gpk: BnG2 = gpk_bytes.into();
+                    let gpk = read_gpk()?;
 
-                    let mut verified = vec![];
-                    if skip {
+                    let vks: Option<Vec<BnG1>> = if skip {
                         // skip verification on partial evaluations
-                        verified = evals;
+                        None
                     } else {
-                        let path = format!("{DKG_DIR}/vks.json");
-                        let bytes = read_to_string(path)?;
-                        let vks_bytes: Vec<Point> = serde_json::from_str(&bytes)?;
-                        let vks: Vec<BnG1> = vks_bytes.iter().map(|vk| vk.into()).collect();
-
-                        for e in evals.into_iter() {
-                            if e.index < 1 || e.index > dkg_config.number_of_members() {
-                                return Err(anyhow!("Invalid member index {:?}", e.index));
-                            }
-
-                            let i = e.index - 1;
-                            if e.verify(&dkg_config, input.as_bytes(), &vks[i]).is_ok() {
-                                verified.push(e);
-                            }
-                        }
-                    }
-
-                    if verified.len() < dkg_config.threshold() {
-                        return Err(anyhow!("Not enough valid partial evaluations"));
-                    }
+                        let vks = read_vks()?;
+                        check_verify_keys(&dkg_config, &vks, &mut rng)?;
+                        Some(vks)
+                    };
 
-                    let pseudo = combine_partial_evaluations(
+                    let combined = combine_partial_evaluations_robust(
                         &dkg_config,
-                        &verified[0..dkg_config.threshold()],
+                        &domain,
+                        input.as_bytes(),
+                        &evals,
+                        vks.as_deref(),
                     )?;
+                    for rejected in combined.rejected.iter() {
+                        info!(
+                            "partial evaluation from member {} rejected: {:?}",
+                            rejected.index, rejected.reason
+                        );
+                    }
+                    let pseudo = combined.pseudo_random;
 
-                    pseudo.verify(input.as_bytes(), &gpk)?;
+                    pseudo.verify(&domain, input.as_bytes(), &gpk)?;
 
-                    let pseudo_bytes: PseudoRandomSerde = pseudo.into();
-                    let serialized = serde_json::to_string(&pseudo_bytes)?;
-                    let path = &format!("{RANDOM_DIR}/pseudo.json");
-                    write(path, serialized.as_bytes())?;
+                    let path = save_artifact(&format!("{RANDOM_DIR}/pseudo"), &pseudo, format)?;
                     info!(
                         "final pseudorandom on input \"{}\" generated and saved at {}",
                         input, path
                     );
                 }
                 RandCommands::VerifyFinal { input } => {
-                    let path = &format!("{RANDOM_DIR}/pseudo.json");
-                    let bytes = read_to_string(path)?;
-                    let pseudo_bytes: PseudoRandomSerde = serde_json::from_str(&bytes)?;
-                    let pseudo: PseudoRandom = pseudo_bytes.into();
+                    let pseudo: PseudoRandom =
+                        read_artifact(&format!("{RANDOM_DIR}/pseudo"), format)?;
 
                     // read dkg global public parameters
-                    let path = format!("{DKG_DIR}/gpk.json");
-                    let bytes = read_to_string(path)?;
-                    let gpk_bytes: Point2 = serde_json::from_str(&bytes)?;
-                    let gpk: BnG2 = gpk_bytes.into();
+                    let gpk = read_gpk()?;
 
-                    pseudo.verify(input.as_bytes(), &gpk)?;
+                    pseudo.verify(&domain, input.as_bytes(), &gpk)?;
                     info!("final pseudorandom on input \"{input}\" verified successfully");
                 }
             }
         }
+        Commands::Plan { circuit } => {
+            let plan = plan(dkg_config, circuit.kind())?;
+            let mib = |bytes: u64| bytes as f64 / (1 << 20) as f64;
+            info!(
+                "minimum degree {} of the {:?} circuit for threshold {}-out-of-{}",
+                plan.k,
+                plan.kind,
+                dkg_config.threshold(),
+                dkg_config.number_of_members()
+            );
+            if let Some(degree) = params.degree {
+                if plan.k > degree {
+                    info!("DEGREE {degree} is too small, use at least {}", plan.k);
+                }
+            }
+            info!("instance size {} field elements", plan.instance_size);
+            info!(
+                "{} advice, {} fixed, {} permutation columns and {} lookups",
+                plan.advice_columns, plan.fixed_columns, plan.permutation_columns, plan.lookups
+            );
+            info!(
+                "estimated vk {:.3} MiB, pk {:.1} MiB, proving memory {:.1} MiB",
+                mib(plan.vk_bytes),
+                mib(plan.pk_bytes),
+                mib(plan.prover_memory_bytes)
+            );
+        }
+        Commands::Migrate => {
+            if key_options.password()?.is_none() {
+                return Err(anyhow!(
+                    "Migration requires --password, --password-env or --password-file"
+                ));
+            }
+            let members = migrate_keys::<MemberKey>(MEMBERS_DIR, "", &key_options, &mut rng)?;
+            let mut shares =
+                migrate_keys::<DkgShareKey>(DKG_SHARES_DIR, "share_", &key_options, &mut rng)?;
+            shares +=
+                migrate_keys::<DkgShareKey>(RESHARE_SHARES_DIR, "share_", &key_options, &mut rng)?;
+            info!("{members} member keys and {shares} secret shares encrypted");
+        }
     }
 
     Ok(())
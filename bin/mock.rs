@@ -1,7 +1,3 @@
-use crate::serialise::{
-    le_bytes_to_hex, DkgGlobalPubParams as DkgGlobalPubParamsSerde,
-    DkgShareKey as DkgShareKeySerde, Point,
-};
 use crate::{
     DKG_DIR, DKG_SECRETS_DIR, DKG_SHARES_DIR, MEMBERS_DIR, MEM_PUBLIC_KEYS_PATH, RANDOM_DIR,
 };
@@ -9,7 +5,8 @@ use anyhow::Result;
 use halo2wrong::curves::bn256::Fr as BnScalar;
 use rand_core::RngCore;
 use std::fs::{read_to_string, write};
-use zkrand::dkg::{DkgConfig, PartialEval};
+use zkrand::dkg::{DkgConfig, EvalDomain, PartialEval};
+use zkrand::serialise::{le_bytes_to_hex, Point, Point2};
 use zkrand::{
     combine_partial_evaluations, dkg_global_public_params, DkgGlobalPubParams, DkgMemberParams,
     DkgMemberPublicParams, DkgShareKey, MemberKey, PseudoRandom,
@@ -23,32 +20,28 @@ fn save_params(
     for (i, dkg) in dkgs.iter().enumerate() {
         let index = i + 1;
         let path = &format!("{DKG_SECRETS_DIR}/secret_{index}.json");
-        let dkg_bytes: crate::serialise::DkgMemberParams = dkg.into();
-        let serialized = serde_json::to_string(&dkg_bytes).unwrap();
+        let serialized = serde_json::to_string(dkg).unwrap();
         write(path, serialized.as_bytes())?;
     }
 
     {
         let path = &format!("{DKG_DIR}/dkgs_public.json");
-        let dkgs_pub_bytes: Vec<crate::serialise::DkgMemberPublicParams> =
-            dkgs_pub.iter().map(|&d| d.into()).collect();
-        let serialized = serde_json::to_string(&dkgs_pub_bytes).unwrap();
+        let serialized = serde_json::to_string(dkgs_pub).unwrap();
         write(path, serialized.as_bytes())?;
     }
 
     {
         let path = &format!("{DKG_DIR}/gpp.json");
-        let gpp_bytes: crate::serialise::DkgGlobalPubParams = gpp.into();
-        let serialized = serde_json::to_string(&gpp_bytes).unwrap();
+        let serialized = serde_json::to_string(gpp).unwrap();
         write(path, serialized.as_bytes())?;
 
         let path = &format!("{DKG_DIR}/gpk.json");
-        let gpk = gpp_bytes.g2a;
+        let gpk: Point2 = gpp.g2a.into();
         let serialized = serde_json::to_string(&gpk).unwrap();
         write(path, serialized.as_bytes())?;
 
         let path = &format!("{DKG_DIR}/vks.json");
-        let vks = gpp_bytes.verify_keys;
+        let vks: Vec<Point> = gpp.verify_keys.iter().map(|vk| vk.into()).collect();
         let serialized = serde_json::to_string(&vks).unwrap();
         write(path, serialized.as_bytes())?;
     }
@@ -60,8 +53,7 @@ fn save_shares(shares: &[DkgShareKey]) -> Result<()> {
     for share in shares.iter() {
         let index = share.index();
         let path = &format!("{DKG_SHARES_DIR}/share_{index}.json");
-        let share_bytes: crate::serialise::DkgShareKey = share.into();
-        let serialized = serde_json::to_string(&share_bytes).unwrap();
+        let serialized = serde_json::to_string(share).unwrap();
         write(path, serialized.as_bytes())?;
     }
 
@@ -69,13 +61,11 @@ fn save_shares(shares: &[DkgShareKey]) -> Result<()> {
 }
 
 fn save_evals(sigmas: &[PartialEval], pseudo: &PseudoRandom) -> Result<()> {
-    let bytes: Vec<crate::serialise::PartialEval> = sigmas.iter().map(|s| s.into()).collect();
-    let seralised = serde_json::to_string(&bytes)?;
+    let seralised = serde_json::to_string(sigmas)?;
     let path = format!("{RANDOM_DIR}/evals.json");
     write(path, &seralised)?;
 
-    let bytes: crate::serialise::PseudoRandom = pseudo.into();
-    let serialised = serde_json::to_string(&bytes)?;
+    let serialised = serde_json::to_string(pseudo)?;
     let path = format!("{RANDOM_DIR}/pseudo.json");
     write(path, &serialised)?;
     Ok(())
@@ -97,22 +87,27 @@ fn save_instances(instances: &[Vec<BnScalar>]) -> Result<()> {
     Ok(())
 }
 
-pub fn mock_members(dkg_config: &DkgConfig, mut rng: impl RngCore) -> Result<()> {
-    let mut mpks_bytes: Vec<Point> = vec![];
-    let mut members_bytes: Vec<crate::serialise::MemberKey> = vec![];
-    for _ in 0..dkg_config.number_of_members() {
-        let member = MemberKey::random(&mut rng);
-        let member_bytes = crate::serialise::MemberKey::from(member.clone());
-        let mpk_bytes = member_bytes.pk.clone();
-
-        mpks_bytes.push(mpk_bytes);
-        members_bytes.push(member_bytes);
+// without g2chip, every dealer publishes its g2a next to its proof, as "dkg prove" does
+#[cfg(not(feature = "g2chip"))]
+fn save_g2as(dkgs_pub: &[&DkgMemberPublicParams]) -> Result<()> {
+    for (i, pp) in dkgs_pub.iter().enumerate() {
+        let path = format!("{}/g2a_{}.json", crate::DKG_PROOFS_DIR, i + 1);
+        let g2a: Point2 = pp.g2a.into();
+        write(path, serde_json::to_string(&g2a)?)?;
     }
+    Ok(())
+}
+
+pub fn mock_members(dkg_config: &DkgConfig, mut rng: impl RngCore) -> Result<()> {
+    let members: Vec<_> = (0..dkg_config.number_of_members())
+        .map(|_| MemberKey::random(&mut rng))
+        .collect();
+    let mpks_bytes: Vec<Point> = members.iter().map(|m| m.public_key().into()).collect();
 
     let serialized = serde_json::to_string(&mpks_bytes)?;
     write(MEM_PUBLIC_KEYS_PATH, &serialized)?;
 
-    for (i, member) in members_bytes.iter().enumerate() {
+    for (i, member) in members.iter().enumerate() {
         let path = format!("{MEMBERS_DIR}/member_{}.json", i + 1);
         let member_serialised = serde_json::to_string(member)?;
         write(path, &member_serialised)?;
@@ -128,8 +123,7 @@ pub fn mock_dkg(dkg_config: &DkgConfig, mut rng: impl RngCore) -> Result<()> {
         let index = i + 1;
         let path = format!("{MEMBERS_DIR}/member_{index}.json");
         let bytes = read_to_string(path)?;
-        let member_bytes: crate::serialise::MemberKey = serde_json::from_str(&bytes)?;
-        let member: MemberKey = member_bytes.into();
+        let member: MemberKey = serde_json::from_str(&bytes)?;
         mpks.push(member.public_key());
         members.push(member);
     }
@@ -142,9 +136,11 @@ pub fn mock_dkg(dkg_config: &DkgConfig, mut rng: impl RngCore) -> Result<()> {
 
     let instances: Vec<_> = dkgs.iter().map(|dkg| dkg.instance()[0].clone()).collect();
     save_instances(&instances)?;
+    #[cfg(not(feature = "g2chip"))]
+    save_g2as(&dkgs_pub)?;
 
     // compute global public parameters
-    let pp = dkg_global_public_params(&dkgs_pub);
+    let pp = dkg_global_public_params(dkg_config, &dkgs_pub)?;
     save_params(&dkgs, &dkgs_pub, &pp)?;
 
     // each member decrypt to obtain their own shares
@@ -169,27 +165,30 @@ pub fn mock_random(dkg_config: &DkgConfig, input: &[u8], mut rng: impl RngCore)
         let index = i + 1;
         let path = format!("{DKG_SHARES_DIR}/share_{index}.json");
         let bytes = read_to_string(path)?;
-        let share_bytes: DkgShareKeySerde = serde_json::from_str(&bytes)?;
-        let share: DkgShareKey = share_bytes.into();
+        let share: DkgShareKey = serde_json::from_str(&bytes)?;
         shares.push(share);
     }
 
     let path = format!("{DKG_DIR}/gpp.json");
     let bytes = read_to_string(path)?;
-    let gpp_bytes: DkgGlobalPubParamsSerde = serde_json::from_str(&bytes)?;
-    let gpp: DkgGlobalPubParams = gpp_bytes.into();
+    let gpp: DkgGlobalPubParams = serde_json::from_str(&bytes)?;
 
     let mut sigmas = vec![];
     for (i, share) in shares.iter().enumerate() {
-        let sigma = share.evaluate(input, &mut rng);
+        let sigma = share.evaluate(&EvalDomain::default(), input, &mut rng);
         sigma
-            .verify(dkg_config, input, &gpp.verify_keys[i])
+            .verify(
+                dkg_config,
+                &EvalDomain::default(),
+                input,
+                &gpp.verify_keys[i],
+            )
             .unwrap();
         sigmas.push(sigma);
     }
 
     let v = combine_partial_evaluations(&dkg_config, &sigmas[0..dkg_config.threshold()]).unwrap();
-    v.verify(input, &gpp.g2a).unwrap();
+    v.verify(&EvalDomain::default(), input, &gpp.g2a).unwrap();
 
     save_evals(&sigmas, &v)?;
 
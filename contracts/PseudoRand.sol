@@ -9,9 +9,17 @@ contract PseudoRand is IPseudoRand{
     using Pairing for *;
     using Hash for *;
 
-    bytes public constant DOMAIN = bytes("DVRF pseudorandom generation 2023");
+    bytes public constant DEFAULT_DOMAIN = bytes("DVRF pseudorandom generation 2023");
     uint public constant R = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
 
+    // domain of hashing messages to G1; it is EvalDomain::prefix() of the evaluations verified
+    // by this contract, and DEFAULT_DOMAIN for an empty domain
+    bytes public DOMAIN;
+
+    constructor(bytes memory domain) {
+        DOMAIN = domain.length == 0 ? DEFAULT_DOMAIN : domain;
+    }
+
     function hashToG1(bytes memory message) public view returns (Pairing.G1Point memory) {
         Pairing.G1Point memory h = Hash.hashToG1(DOMAIN, message);
         return h;
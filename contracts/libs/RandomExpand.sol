@@ -0,0 +1,64 @@
+pragma solidity ^0.8.0;
+
+// expansion of a pseudorandom value, matching src/expand.rs bit for bit
+library RandomExpand {
+    // word_i = keccak256(value || uint256(i))
+    function word(bytes32 value, uint256 counter) internal pure returns (uint256) {
+        return uint256(keccak256(abi.encodePacked(value, counter)));
+    }
+
+    // unbiased integer in [0, n) drawn from the words starting at counter; returns the next counter
+    function uniform(bytes32 value, uint256 counter, uint256 n) internal pure returns (uint256, uint256) {
+        require(n > 0, "Empty range");
+        // 2^256 mod n; the words >= threshold cover a multiple of n values
+        uint256 threshold;
+        unchecked {
+            threshold = (0 - n) % n;
+        }
+
+        while (true) {
+            uint256 w = word(value, counter);
+            counter++;
+            if (w >= threshold) {
+                return (w % n, counter);
+            }
+        }
+    }
+
+    // Fisher-Yates shuffle: for i = len - 1 down to 1, swap a[i] and a[uniform(i + 1)]
+    function shuffle(bytes32 value, uint256[] memory items) internal pure returns (uint256[] memory) {
+        uint256 counter = 0;
+        for (uint256 i = items.length; i > 1; i--) {
+            uint256 j;
+            (j, counter) = uniform(value, counter, i);
+            (items[i - 1], items[j]) = (items[j], items[i - 1]);
+        }
+        return items;
+    }
+
+    // k distinct integers from [0, n) by the first k steps of Fisher-Yates on 0..n
+    function sample(bytes32 value, uint256 k, uint256 n) internal pure returns (uint256[] memory) {
+        require(k <= n, "Sample larger than range");
+        uint256[] memory items = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            items[i] = i;
+        }
+
+        uint256 counter = 0;
+        uint256[] memory sampled = new uint256[](k);
+        for (uint256 i = 0; i < k; i++) {
+            uint256 j;
+            (j, counter) = uniform(value, counter, n - i);
+            j += i;
+            (items[i], items[j]) = (items[j], items[i]);
+            sampled[i] = items[i];
+        }
+        return sampled;
+    }
+
+    // test vectors, see test_expand_vectors in src/expand.rs:
+    // value = 0x000102...1f
+    // uniform with n = 1, 2, 6, 100, 2^64 - 1 from counter 0: 0, 0, 4, 30, 18318269273721899236
+    // shuffle of [0, ..., 9]: [7, 5, 6, 1, 3, 8, 0, 2, 9, 4]
+    // sample(3, 10): [4, 5, 0]
+}
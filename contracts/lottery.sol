@@ -2,6 +2,7 @@
 pragma solidity ^0.8.0;
 
 import {zkdvrf} from "./zkdvrf.sol";
+import {RandomExpand} from "./libs/RandomExpand.sol";
 
 import "@openzeppelin/contracts/utils/Strings.sol";
 import '@openzeppelin/contracts/access/Ownable.sol';
@@ -57,16 +58,15 @@ contract Lottery is Ownable {
         hasEntered[msg.sender] = true;
     }
 
-    // Fisher-Yates Shuffle
+    // Fisher-Yates Shuffle with unbiased draws, the same permutation as PseudoRandom::shuffle
     function shuffle() private {
         require(randValue != 0x00, "Random not ready yet");
 
-        for (uint i = 0; i < players.length; i++) {
-            bytes32 randomBytes = keccak256(abi.encodePacked(randValue, i));
-            uint256 random = uint256(randomBytes);
-
-            uint j = random % (i + 1);
-            (players[i], players[j]) = (players[j], players[i]);
+        uint256 counter = 0;
+        for (uint i = players.length; i > 1; i--) {
+            uint j;
+            (j, counter) = RandomExpand.uniform(randValue, counter, i);
+            (players[i - 1], players[j]) = (players[j], players[i - 1]);
         }
     }
 
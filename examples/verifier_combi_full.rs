@@ -14,7 +14,7 @@ use rand_core::{OsRng, RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::fs::{create_dir_all, File};
 use std::io::Write;
-use zkrand::dkg::{DkgConfig, PartialEval};
+use zkrand::dkg::{DkgConfig, EvalDomain, PartialEval};
 use zkrand::{
     combine_partial_evaluations, dkg_global_public_params, hash_to_curve_bn, load_or_create_params,
     load_or_create_pk, DkgGlobalPubParams, DkgMemberParams, MemberKey, PseudoRandom, EVAL_PREFIX,
@@ -204,7 +204,7 @@ fn main() {
     let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
 
     // compute global public parameters
-    let pp = dkg_global_public_params(&dkgs_pub);
+    let pp = dkg_global_public_params(&dkg_config, &dkgs_pub).unwrap();
     save_params(&pp);
 
     // all the instances
@@ -286,9 +286,14 @@ fn main() {
 
     let mut sigmas = vec![];
     for i in 0..number_of_members {
-        let sigma = shares[i].evaluate(input, &mut rng);
+        let sigma = shares[i].evaluate(&EvalDomain::default(), input, &mut rng);
         sigma
-            .verify(&dkg_config, input, &pp.verify_keys[i])
+            .verify(
+                &dkg_config,
+                &EvalDomain::default(),
+                input,
+                &pp.verify_keys[i],
+            )
             .unwrap();
         sigmas.push(sigma);
     }
@@ -298,5 +303,5 @@ fn main() {
     // combine partial evaluations to obtain final random
     let v = combine_partial_evaluations(&dkg_config, &sigmas[0..threshold]).unwrap();
     save_pseudo(&v);
-    v.verify(input, &pp.g2a).unwrap();
+    v.verify(&EvalDomain::default(), input, &pp.g2a).unwrap();
 }
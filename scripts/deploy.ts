@@ -11,6 +11,8 @@ async function main() {
     const degree = process.env.DEGREE
     const minDeposit = process.env.MIN_DEPOSIT ?? '0'
     const deployNoHelpers = process.env.DEPLOY_NO_HELPERS === 'true'
+    // EvalDomain::prefix() of the client's --chain-id/--contract/--app options, empty for the default domain
+    const evalDomain = process.env.EVAL_DOMAIN ?? ''
 
     let halo2VerifierAddress
     let halo2VerifyingKeyAddress
@@ -42,7 +44,7 @@ async function main() {
         console.log("GlobalPublicParams deployed at", globalPublicParams.address)
 
         const PseudoRand = await ethers.getContractFactory('PseudoRand')
-        const pseudoRand = await PseudoRand.connect(deployerWallet).deploy()
+        const pseudoRand = await PseudoRand.connect(deployerWallet).deploy(utils.toUtf8Bytes(evalDomain))
         await pseudoRand.deployed()
 
         console.log("PseudoRand deployed at", pseudoRand.address)
@@ -0,0 +1,245 @@
+use crate::dkg::DkgConfig;
+use crate::error::Error;
+use crate::hash_to_curve_evm::from_be_bytes;
+use crate::secret::Secret;
+use crate::{encryption_key, DkgMemberPublicParams, MemberKey};
+use halo2wrong::curves::bn256::{Fr as BnScalar, G1Affine as BnG1};
+use halo2wrong::curves::group::Curve;
+use halo2wrong::curves::grumpkin::{Fr as GkScalar, G1Affine as GkG1};
+use halo2wrong::halo2::arithmetic::Field;
+use rand_core::RngCore;
+use sha3::{Digest, Keccak256};
+
+// a schnorr style proof that log_g(pk) == log_gr(pkr) on Grumpkin
+#[derive(Debug, Clone)]
+pub struct DleqProof {
+    pub z: GkScalar,
+    pub c: GkScalar,
+}
+
+// a publicly verifiable accusation against a dealer whose encrypted share is invalid;
+// it reveals the decryption key gr^msk for one dealing without revealing msk
+#[derive(Debug, Clone)]
+pub struct Complaint {
+    // index of the accusing member, between 1...NUMBER_OF_MEMBERS
+    pub accuser: usize,
+    // position of the accused dealer in the list of public parameters, starting from 1
+    pub dealer: usize,
+    // pkr = gr^msk
+    pub pkr: GkG1,
+    pub proof: DleqProof,
+}
+
+fn dleq_challenge(points: &[GkG1]) -> GkScalar {
+    // same byte order as the challenge of partial evaluations
+    let mut bytes = vec![];
+    for p in points.iter().rev() {
+        bytes.extend(p.y.to_bytes());
+        bytes.extend(p.x.to_bytes());
+    }
+    bytes.reverse();
+
+    let hash_state: [u8; 32] = Keccak256::new()
+        .chain_update(&bytes)
+        .finalize()
+        .to_vec()
+        .try_into()
+        .unwrap();
+
+    GkScalar::from_raw(from_be_bytes(&hash_state))
+}
+
+impl MemberKey {
+    // decrypt the share dealt to member index and check it against the dealer's public share
+    pub fn verify_share(
+        &self,
+        dkg_config: &DkgConfig,
+        index: usize,
+        pp: &DkgMemberPublicParams,
+    ) -> Result<BnScalar, Error> {
+        if index < 1 || index > dkg_config.number_of_members() {
+            return Err(Error::InvalidIndex { index });
+        }
+        pp.check_lengths(dkg_config)?;
+
+        let k = index - 1;
+        let s = self.decrypt_share(&pp.gr, &pp.ciphers[k]);
+        let gs = (BnG1::generator() * s).to_affine();
+        if gs != pp.public_shares[k] {
+            return Err(Error::InvalidShare { index });
+        }
+
+        Ok(s)
+    }
+
+    // create a complaint against the dealer at position "dealer" (starting from 1)
+    pub fn complain(
+        &self,
+        index: usize,
+        dealer: usize,
+        pp: &DkgMemberPublicParams,
+        mut rng: impl RngCore,
+    ) -> Complaint {
+        let g = GkG1::generator();
+        let sk = self.sk.expose_secret();
+        let pkr = (pp.gr * sk).to_affine();
+
+        let r = Secret::new(GkScalar::random(&mut rng));
+        let cap_r_1 = (g * r.expose_secret()).to_affine();
+        let cap_r_2 = (pp.gr * r.expose_secret()).to_affine();
+
+        let c = dleq_challenge(&[g, self.pk, pp.gr, pkr, cap_r_1, cap_r_2]);
+        let z = c * sk + r.expose_secret();
+
+        Complaint {
+            accuser: index,
+            dealer,
+            pkr,
+            proof: DleqProof { z, c },
+        }
+    }
+
+    // check the shares from all dealers and create complaints for the invalid ones
+    pub fn check_shares(
+        &self,
+        dkg_config: &DkgConfig,
+        index: usize,
+        pps: &[&DkgMemberPublicParams],
+        mut rng: impl RngCore,
+    ) -> Result<Vec<Complaint>, Error> {
+        // malformed dealings are rejected before any share is decrypted
+        for &pp in pps.iter() {
+            pp.check_lengths(dkg_config)?;
+        }
+
+        let mut complaints = vec![];
+        for (i, &pp) in pps.iter().enumerate() {
+            match self.verify_share(dkg_config, index, pp) {
+                Ok(_) => {}
+                Err(Error::InvalidShare { .. }) => {
+                    complaints.push(self.complain(index, i + 1, pp, &mut rng))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(complaints)
+    }
+}
+
+impl Complaint {
+    // verify that the accuser's decryption key is correct and the decrypted share is invalid;
+    // pks are the member public keys and pp is the public parameters of the accused dealer
+    pub fn verify(
+        &self,
+        dkg_config: &DkgConfig,
+        pks: &[GkG1],
+        pp: &DkgMemberPublicParams,
+    ) -> Result<(), Error> {
+        if self.accuser < 1 || self.accuser > dkg_config.number_of_members() {
+            return Err(Error::InvalidIndex {
+                index: self.accuser,
+            });
+        }
+
+        pp.check_lengths(dkg_config)?;
+        if pks.len() != dkg_config.number_of_members() {
+            return Err(Error::VerifyFailed);
+        }
+
+        let k = self.accuser - 1;
+        let pk = pks[k];
+        let g = GkG1::generator();
+        let z = self.proof.z;
+        let c = self.proof.c;
+
+        let cap_r_1 = ((g * z) - (pk * c)).to_affine();
+        let cap_r_2 = ((pp.gr * z) - (self.pkr * c)).to_affine();
+        let c_tilde = dleq_challenge(&[g, pk, pp.gr, self.pkr, cap_r_1, cap_r_2]);
+
+        if c != c_tilde {
+            return Err(Error::VerifyFailed);
+        }
+
+        // the accusation only holds if the decrypted share does not match the public share
+        let s = pp.ciphers[k] - encryption_key(&self.pkr);
+        let gs = (BnG1::generator() * s).to_affine();
+        if gs == pp.public_shares[k] {
+            return Err(Error::InvalidComplaint {
+                dealer: self.dealer,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DkgMemberParams;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_complaint() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let members: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| MemberKey::random(&mut rng))
+            .collect();
+        let pks: Vec<_> = members.iter().map(|m| m.public_key()).collect();
+
+        let mut dkgs: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| DkgMemberParams::new(dkg_config, pks.clone(), &mut rng).unwrap())
+            .collect();
+
+        // dealer 2 sends a bad share to member 4
+        let (dealer, accuser) = (2, 4);
+        dkgs[dealer - 1].public_params.ciphers[accuser - 1] += BnScalar::one();
+
+        let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
+
+        for (i, member) in members.iter().enumerate() {
+            let complaints = member
+                .check_shares(&dkg_config, i + 1, &dkgs_pub, &mut rng)
+                .unwrap();
+            if i + 1 == accuser {
+                assert_eq!(complaints.len(), 1);
+                let complaint = &complaints[0];
+                assert_eq!(complaint.dealer, dealer);
+                complaint
+                    .verify(&dkg_config, &pks, dkgs_pub[dealer - 1])
+                    .unwrap();
+            } else {
+                assert!(complaints.is_empty());
+            }
+        }
+
+        // a complaint against an honest dealer is rejected
+        let complaint = members[0].complain(1, 1, dkgs_pub[0], &mut rng);
+        assert!(complaint.verify(&dkg_config, &pks, dkgs_pub[0]).is_err());
+
+        // a complaint with a wrong decryption key is rejected
+        let mut complaint =
+            members[accuser - 1].complain(accuser, dealer, dkgs_pub[dealer - 1], &mut rng);
+        complaint.pkr = GkG1::random(&mut rng);
+        assert!(complaint
+            .verify(&dkg_config, &pks, dkgs_pub[dealer - 1])
+            .is_err());
+
+        // a dealing without a cipher for every member is rejected instead of indexed
+        let mut short = dkgs_pub[0].clone();
+        short.ciphers.pop();
+        assert!(matches!(
+            members[4].verify_share(&dkg_config, 5, &short),
+            Err(Error::LengthMismatch {
+                expected: 5,
+                actual: 4
+            })
+        ));
+        assert!(members[0]
+            .check_shares(&dkg_config, 1, &[&short], &mut rng)
+            .is_err());
+    }
+}
@@ -0,0 +1,157 @@
+// compressed instance: the circuit of DkgCircuit::new_compressed exposes a single poseidon
+// commitment to the public data of instance() instead of the 7n + 14 field elements, so the
+// cost of the verifier does not grow with the number of members. The public data is published
+// separately and accepted if it opens the proven commitment
+use crate::poseidon::permute;
+use crate::{DkgCircuit, DkgMemberParams, DkgMemberPublicParams, POSEIDON_RATE, POSEIDON_WIDTH};
+use halo2_gadgets::poseidon::primitives::Domain;
+use halo2wrong::curves::bn256::Fr as BnScalar;
+use halo2wrong::curves::grumpkin::G1Affine as GkG1;
+use halo2wrong::halo2::arithmetic::Field;
+use halo2wrong::halo2::circuit::Value;
+use rand_core::RngCore;
+
+// sponge domain of the commitment: the public data is padded with zeros to a multiple of the
+// rate, which is unambiguous as its length is fixed by the dkg config
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PublicData;
+
+impl Domain<BnScalar, POSEIDON_RATE> for PublicData {
+    type Padding = Vec<BnScalar>;
+
+    fn name() -> String {
+        "PublicData".to_string()
+    }
+
+    fn initial_capacity_element() -> BnScalar {
+        BnScalar::zero()
+    }
+
+    fn padding(input_len: usize) -> Self::Padding {
+        let k = (POSEIDON_RATE - input_len % POSEIDON_RATE) % POSEIDON_RATE;
+        vec![BnScalar::zero(); k]
+    }
+}
+
+// poseidon sponge absorbing the public data two elements per permutation, the same as in
+// DkgCircuit::synthesize
+pub fn commit_public_data(public_data: &[BnScalar]) -> BnScalar {
+    let mut state = [BnScalar::zero(); POSEIDON_WIDTH];
+    state[POSEIDON_RATE] = PublicData::initial_capacity_element();
+
+    let padded: Vec<_> = public_data
+        .iter()
+        .copied()
+        .chain(PublicData::padding(public_data.len()))
+        .collect();
+    for chunk in padded.chunks(POSEIDON_RATE) {
+        for (word, x) in state.iter_mut().zip(chunk.iter()) {
+            *word += x;
+        }
+        permute(&mut state);
+    }
+
+    state[0]
+}
+
+impl DkgMemberParams {
+    pub fn compressed_circuit(&self, mut rng: impl RngCore) -> DkgCircuit {
+        let coeffs: Vec<_> = self
+            .coeffs
+            .expose_secret()
+            .iter()
+            .map(|a| Value::known(*a))
+            .collect();
+        let public_keys: Vec<_> = self
+            .public_keys
+            .iter()
+            .map(|pk| Value::known(*pk))
+            .collect();
+
+        let grumpkin_aux_generator = Value::known(GkG1::random(&mut rng));
+        DkgCircuit::new_compressed(
+            self.dkg_config,
+            coeffs,
+            Value::known(*self.r.expose_secret()),
+            public_keys,
+            grumpkin_aux_generator,
+        )
+    }
+
+    pub fn compressed_instance(&self) -> Vec<Vec<BnScalar>> {
+        self.public_params.compressed_instance(&self.public_keys)
+    }
+}
+
+impl DkgMemberPublicParams {
+    // commitment to the public data in instance(pks)
+    pub fn commitment(&self, pks: &[GkG1]) -> BnScalar {
+        commit_public_data(&self.instance(pks)[0])
+    }
+
+    pub fn compressed_instance(&self, pks: &[GkG1]) -> Vec<Vec<BnScalar>> {
+        vec![vec![self.commitment(pks)]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::DkgConfig;
+    use crate::MemberKey;
+    use halo2wrong::curves::ff::PrimeField;
+    use halo2wrong::halo2::dev::MockProver;
+    use halo2wrong::utils::{mock_prover_verify, DimensionMeasurement};
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_compressed_circuit() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let pks: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| MemberKey::random(&mut rng).public_key())
+            .collect();
+
+        let dkg = DkgMemberParams::new(dkg_config, pks.clone(), &mut rng).unwrap();
+        let circuit = dkg.compressed_circuit(&mut rng);
+        let instance = dkg.compressed_instance();
+        assert_eq!(instance[0].len(), 1);
+        mock_prover_verify(&circuit, instance.clone());
+
+        // the published public data opens the commitment
+        let public_data = &dkg.instance()[0];
+        assert_eq!(public_data.len(), dkg_config.instance_size());
+        assert_eq!(commit_public_data(public_data), instance[0][0]);
+        assert_eq!(dkg.public_params.commitment(&pks), instance[0][0]);
+
+        let mut tampered = public_data.clone();
+        tampered[dkg_config.instance_size() - 1] += BnScalar::one();
+        assert_ne!(commit_public_data(&tampered), instance[0][0]);
+
+        // a wrong commitment does not verify
+        let wrong = vec![vec![commit_public_data(&tampered)]];
+        let dimension = DimensionMeasurement::measure(&circuit).unwrap();
+        let prover = MockProver::run(dimension.k(), &circuit, wrong).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_commit_public_data() {
+        let data: Vec<_> = (1..=5u64).map(BnScalar::from).collect();
+
+        // an even length needs no padding
+        let expected = BnScalar::from_str_vartime(
+            "17020742996613313935733890555959673450199072671322126326502444500416373455775",
+        )
+        .unwrap();
+        assert_eq!(commit_public_data(&data[..4]), expected);
+
+        // an odd length is padded with a zero
+        let expected = BnScalar::from_str_vartime(
+            "2773336181622110816007837449589307509523264959926516348586118182902322270856",
+        )
+        .unwrap();
+        assert_eq!(commit_public_data(&data), expected);
+    }
+}
@@ -1,18 +1,78 @@
 use crate::error::Error;
 use crate::hash_to_curve_evm::from_be_bytes;
-use crate::utils::hash_to_curve_bn;
+use crate::secret::Secret;
+use crate::utils::{check_length, hash_to_curve_bn};
 use halo2_ecc::halo2::halo2curves::bn256::G2Prepared;
 use halo2_maingate::halo2::halo2curves::bn256::multi_miller_loop;
-use halo2wrong::curves::bn256::{Fr as BnScalar, G1Affine as BnG1, G2Affine as BnG2};
+use halo2wrong::curves::bn256::{
+    Fr as BnScalar, G1Affine as BnG1, G2Affine as BnG2, G1 as BnG1Curve,
+};
+use halo2wrong::curves::ff::BatchInvert;
+use halo2wrong::curves::group::prime::PrimeCurveAffine;
 use halo2wrong::curves::group::{Curve, Group};
 use halo2wrong::curves::pairing::MillerLoopResult;
-use halo2wrong::halo2::arithmetic::Field;
+use halo2wrong::halo2::arithmetic::{best_multiexp, Field};
+use hmac::{Mac, SimpleHmac};
 use rand_core::RngCore;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
+use zeroize::Zeroize;
 
 pub const EVAL_PREFIX: &str = "DVRF pseudorandom generation 2023";
 
+// hash an input to Bn256 G1; partial evaluations, their verification and the final
+// pseudorandom verification are generic over the hash, so the evaluations can be checked
+// by the verifier contract (EvalDomain) or by non-EVM verifiers (Rfc9380Domain)
+pub trait HashToCurve {
+    fn hash_to_curve(&self, input: &[u8]) -> BnG1;
+}
+
+// domain separation for hashing the input of a pseudorandom evaluation to curve;
+// the default domain is EVAL_PREFIX, which PseudoRand.sol uses when deployed with an empty domain
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EvalDomain {
+    prefix: String,
+}
+
+impl Default for EvalDomain {
+    fn default() -> Self {
+        EvalDomain {
+            prefix: EVAL_PREFIX.to_string(),
+        }
+    }
+}
+
+impl EvalDomain {
+    // separate the outputs of a deployment on a chain; PseudoRand.sol of the deployment
+    // is constructed with the string returned by prefix()
+    pub fn new(chain_id: u64, contract: &[u8; 20], tag: &str) -> Self {
+        let address: String = contract.iter().map(|b| format!("{b:02x}")).collect();
+        EvalDomain {
+            prefix: format!("{EVAL_PREFIX} chain {chain_id} contract 0x{address} app {tag}"),
+        }
+    }
+
+    pub(crate) fn from_prefix(prefix: &str) -> Self {
+        EvalDomain {
+            prefix: prefix.to_string(),
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
+// the keccak based hash of contracts/libs/Hash.sol under this domain
+impl HashToCurve for EvalDomain {
+    fn hash_to_curve(&self, input: &[u8]) -> BnG1 {
+        let hasher = hash_to_curve_bn(&self.prefix);
+        hasher(input).to_affine()
+    }
+}
+
 // evaluate a polynomial at index i
 fn evaluate_poly(coeffs: &[BnScalar], i: usize) -> BnScalar {
     assert!(coeffs.len() >= 1);
@@ -29,7 +89,8 @@ fn evaluate_poly(coeffs: &[BnScalar], i: usize) -> BnScalar {
     eval
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DkgConfig {
     threshold: usize,
     number_of_members: usize,
@@ -67,6 +128,11 @@ impl DkgConfig {
 
         length
     }
+
+    // a refresh dealing does not expose ga and g2a
+    pub fn refresh_instance_size(&self) -> usize {
+        7 * self.number_of_members() + 2
+    }
 }
 
 // compute secret shares for n parties
@@ -85,16 +151,27 @@ pub fn shares(number_of_members: usize, coeffs: &[BnScalar]) -> Vec<BnScalar> {
 
 pub struct DkgShareKey {
     index: usize,
-    sk: BnScalar,
+    sk: Secret<BnScalar>,
     vk: BnG1,
+    // number of refreshes applied to the share derived from the NIDKG
+    epoch: u64,
 }
 
 impl DkgShareKey {
     pub fn new(index: usize, sk: BnScalar, vk: BnG1) -> Self {
-        DkgShareKey { index, sk, vk }
+        Self::new_with_epoch(index, sk, vk, 0)
+    }
+
+    pub fn new_with_epoch(index: usize, sk: BnScalar, vk: BnG1, epoch: u64) -> Self {
+        DkgShareKey {
+            index,
+            sk: Secret::new(sk),
+            vk,
+            epoch,
+        }
     }
-    pub fn secret_key(&self) -> BnScalar {
-        self.sk
+    pub fn secret_key(&self) -> &BnScalar {
+        self.sk.expose_secret()
     }
     pub fn verify_key(&self) -> BnG1 {
         self.vk
@@ -104,6 +181,18 @@ impl DkgShareKey {
         self.index
     }
 
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    // add the sum of the refresh shares to obtain the share key of the next epoch
+    pub fn refresh(&self, delta: BnScalar) -> Self {
+        let sk = *self.secret_key() + delta;
+        let vk = (BnG1::generator() * sk).to_affine();
+
+        DkgShareKey::new_with_epoch(self.index, sk, vk, self.epoch + 1)
+    }
+
     // verify the index and verification key is correct w.r.t. a list of public verification keys
     pub fn verify(&self, dkg_config: &DkgConfig, vks: &[BnG1]) -> Result<(), Error> {
         if self.index < 1 || self.index > dkg_config.number_of_members {
@@ -118,41 +207,77 @@ impl DkgShareKey {
     }
 
     // compute H(x)^sk to create partial evaluation and create a schnorr style proof
-    pub fn evaluate(&self, input: &[u8], mut rng: impl RngCore) -> PartialEval {
-        let hasher = hash_to_curve_bn(EVAL_PREFIX);
-        let h: BnG1 = hasher(input).to_affine();
-        let v = (h * self.sk).to_affine();
+    pub fn evaluate(
+        &self,
+        domain: &impl HashToCurve,
+        input: &[u8],
+        mut rng: impl RngCore,
+    ) -> PartialEval {
+        let h = domain.hash_to_curve(input);
+        let r = Secret::new(BnScalar::random(&mut rng));
+
+        self.evaluate_with_nonce(&h, r)
+    }
 
-        let g = BnG1::generator();
-        let r = BnScalar::random(&mut rng);
-        let cap_r_1 = (g * r).to_affine();
-        let cap_r_2 = (h * r).to_affine();
-
-        let mut bytes = v.y.to_bytes().to_vec();
-        bytes.extend(v.x.to_bytes());
-        bytes.extend(self.vk.y.to_bytes());
-        bytes.extend(self.vk.x.to_bytes());
-        bytes.extend(cap_r_2.y.to_bytes());
-        bytes.extend(cap_r_2.x.to_bytes());
-        bytes.extend(cap_r_1.y.to_bytes());
-        bytes.extend(cap_r_1.x.to_bytes());
-        bytes.extend(h.y.to_bytes());
-        bytes.extend(h.x.to_bytes());
-        bytes.extend(g.y.to_bytes());
-        bytes.extend(g.x.to_bytes());
-        bytes.reverse();
+    // same as evaluate, but the nonce of the proof is derived from sk, H(x) and x (RFC 6979)
+    pub fn evaluate_deterministic(&self, domain: &impl HashToCurve, input: &[u8]) -> PartialEval {
+        let h = domain.hash_to_curve(input);
+        let r = deterministic_nonce(self.sk.expose_secret(), &h, input, &[]);
 
-        let hash_state: [u8; 32] = Keccak256::new()
-            .chain_update(&bytes)
-            .finalize()
-            .to_vec()
-            .try_into()
-            .unwrap();
-        let c = BnScalar::from_raw(from_be_bytes(&hash_state));
-        let z = c * self.sk + r;
-        let proof = PartialEvalProof { z, c };
+        self.evaluate_with_nonce(&h, r)
+    }
 
-        PartialEval {
+    // deterministic nonce hedged with extra randomness, so a broken rng cannot leak sk
+    pub fn evaluate_hedged(
+        &self,
+        domain: &impl HashToCurve,
+        input: &[u8],
+        mut rng: impl RngCore,
+    ) -> PartialEval {
+        let h = domain.hash_to_curve(input);
+        let mut extra = [0u8; 32];
+        rng.fill_bytes(&mut extra);
+        let r = deterministic_nonce(self.sk.expose_secret(), &h, input, &extra);
+
+        self.evaluate_with_nonce(&h, r)
+    }
+
+    // same as evaluate, but the proof is given in the batchable form (R1, R2, z)
+    pub fn evaluate_batchable(
+        &self,
+        domain: &impl HashToCurve,
+        input: &[u8],
+        mut rng: impl RngCore,
+    ) -> BatchablePartialEval {
+        let h = domain.hash_to_curve(input);
+        let r = Secret::new(BnScalar::random(&mut rng));
+
+        self.batchable_with_nonce(&h, r)
+    }
+
+    fn evaluate_with_nonce(&self, h: &BnG1, r: Secret<BnScalar>) -> PartialEval {
+        self.batchable_with_nonce(h, r)
+            .compact_with_hash(h, &self.vk)
+    }
+
+    fn batchable_with_nonce(&self, h: &BnG1, r: Secret<BnScalar>) -> BatchablePartialEval {
+        let h = *h;
+        let sk = self.sk.expose_secret();
+        let v = (h * sk).to_affine();
+
+        let g = BnG1::generator();
+        let cap_r_1 = (g * r.expose_secret()).to_affine();
+        let cap_r_2 = (h * r.expose_secret()).to_affine();
+
+        let c = eval_challenge(&g, &h, &cap_r_1, &cap_r_2, &self.vk, &v);
+        let z = c * sk + r.expose_secret();
+        let proof = BatchableEvalProof {
+            cap_r_1,
+            cap_r_2,
+            z,
+        };
+
+        BatchablePartialEval {
             index: self.index,
             value: v,
             proof,
@@ -160,6 +285,109 @@ impl DkgShareKey {
     }
 }
 
+fn hmac_keccak(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    let mut mac =
+        SimpleHmac::<Keccak256>::new_from_slice(key).expect("hmac accepts keys of any length");
+    for d in data.iter() {
+        mac.update(d);
+    }
+
+    mac.finalize().into_bytes().to_vec().try_into().unwrap()
+}
+
+// bits2int of RFC 6979 section 2.3.2 for a 32 byte string: its leftmost qlen = 254 bits as a
+// big-endian integer
+fn bits2int(b: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[0] = b[0] >> 2;
+    for (o, w) in out[1..].iter_mut().zip(b.windows(2)) {
+        *o = (w[0] << 6) | (w[1] >> 2);
+    }
+    out
+}
+
+// derive the nonce for the proof of a partial evaluation following RFC 6979 section 3.2
+// with HMAC-Keccak256; the message is H(x) || x and extra randomness is added as in section 3.6
+fn deterministic_nonce(sk: &BnScalar, h: &BnG1, input: &[u8], extra: &[u8]) -> Secret<BnScalar> {
+    // int2octets(sk)
+    let mut x = Secret::new(sk.to_bytes());
+    x.expose_secret_mut().reverse();
+    let x = x.expose_secret();
+
+    // bits2octets(hash(m))
+    let digest: [u8; 32] = Keccak256::new()
+        .chain_update(h.x.to_bytes())
+        .chain_update(h.y.to_bytes())
+        .chain_update(input)
+        .finalize()
+        .to_vec()
+        .try_into()
+        .unwrap();
+    let mut h1 = BnScalar::from_raw(from_be_bytes(&bits2int(&digest))).to_bytes();
+    h1.reverse();
+
+    let mut v = [1u8; 32];
+    let mut k = [0u8; 32];
+    k = hmac_keccak(&k, &[&v, &[0x00], x, &h1, extra]);
+    v = hmac_keccak(&k, &[&v]);
+    k = hmac_keccak(&k, &[&v, &[0x01], x, &h1, extra]);
+    v = hmac_keccak(&k, &[&v]);
+
+    loop {
+        v = hmac_keccak(&k, &[&v]);
+
+        // bits2int(v), accepted if it is in [1, q-1]
+        let mut t = Secret::new(bits2int(&v));
+        t.expose_secret_mut().reverse();
+        let r: Option<BnScalar> = BnScalar::from_bytes(t.expose_secret()).into();
+        if let Some(r) = r {
+            if !bool::from(r.is_zero()) {
+                // the hmac state determines the nonce
+                k.zeroize();
+                v.zeroize();
+                return Secret::new(r);
+            }
+        }
+
+        k = hmac_keccak(&k, &[&v, &[0x00]]);
+        v = hmac_keccak(&k, &[&v]);
+    }
+}
+
+// challenge of the schnorr style proof for partial evaluations
+fn eval_challenge(
+    g: &BnG1,
+    h: &BnG1,
+    cap_r_1: &BnG1,
+    cap_r_2: &BnG1,
+    vk: &BnG1,
+    v: &BnG1,
+) -> BnScalar {
+    // reverse order to match solidity version
+    let mut bytes = v.y.to_bytes().to_vec();
+    bytes.extend(v.x.to_bytes());
+    bytes.extend(vk.y.to_bytes());
+    bytes.extend(vk.x.to_bytes());
+    bytes.extend(cap_r_2.y.to_bytes());
+    bytes.extend(cap_r_2.x.to_bytes());
+    bytes.extend(cap_r_1.y.to_bytes());
+    bytes.extend(cap_r_1.x.to_bytes());
+    bytes.extend(h.y.to_bytes());
+    bytes.extend(h.x.to_bytes());
+    bytes.extend(g.y.to_bytes());
+    bytes.extend(g.x.to_bytes());
+    bytes.reverse();
+
+    let hash_state: [u8; 32] = Keccak256::new()
+        .chain_update(&bytes)
+        .finalize()
+        .to_vec()
+        .try_into()
+        .unwrap();
+
+    BnScalar::from_raw(from_be_bytes(&hash_state))
+}
+
 #[derive(Debug, Clone)]
 pub struct PartialEvalProof {
     pub z: BnScalar,
@@ -174,13 +402,18 @@ pub struct PartialEval {
 }
 
 impl PartialEval {
-    pub fn verify(&self, dkg_config: &DkgConfig, input: &[u8], vk: &BnG1) -> Result<(), Error> {
+    pub fn verify(
+        &self,
+        dkg_config: &DkgConfig,
+        domain: &impl HashToCurve,
+        input: &[u8],
+        vk: &BnG1,
+    ) -> Result<(), Error> {
         if self.index > dkg_config.number_of_members || self.index < 1 {
             return Err(Error::InvalidIndex { index: self.index });
         };
 
-        let hasher = hash_to_curve_bn(EVAL_PREFIX);
-        let h: BnG1 = hasher(input).to_affine();
+        let h = domain.hash_to_curve(input);
 
         let g = BnG1::generator();
         let z = self.proof.z;
@@ -190,28 +423,7 @@ impl PartialEval {
         let cap_r_1 = ((g * z) - (vk * c)).to_affine();
         let cap_r_2 = ((h * z) - (v * c)).to_affine();
 
-        // reverse order to match solidity version
-        let mut bytes = v.y.to_bytes().to_vec();
-        bytes.extend(v.x.to_bytes());
-        bytes.extend(vk.y.to_bytes());
-        bytes.extend(vk.x.to_bytes());
-        bytes.extend(cap_r_2.y.to_bytes());
-        bytes.extend(cap_r_2.x.to_bytes());
-        bytes.extend(cap_r_1.y.to_bytes());
-        bytes.extend(cap_r_1.x.to_bytes());
-        bytes.extend(h.y.to_bytes());
-        bytes.extend(h.x.to_bytes());
-        bytes.extend(g.y.to_bytes());
-        bytes.extend(g.x.to_bytes());
-        bytes.reverse();
-
-        let hash_state: [u8; 32] = Keccak256::new()
-            .chain_update(&bytes)
-            .finalize()
-            .to_vec()
-            .try_into()
-            .unwrap();
-        let c_tilde = BnScalar::from_raw(from_be_bytes(&hash_state));
+        let c_tilde = eval_challenge(&g, &h, &cap_r_1, &cap_r_2, vk, &v);
 
         if c != c_tilde {
             return Err(Error::VerifyFailed);
@@ -221,13 +433,219 @@ impl PartialEval {
     }
 }
 
+// the proof of a partial evaluation in the batchable form: R1 = g^r and R2 = h^r are sent instead
+// of the challenge c, which the verifier recomputes from them. The proofs of many evaluations can then
+// be checked together with a random linear combination
+#[derive(Debug, Clone)]
+pub struct BatchableEvalProof {
+    pub cap_r_1: BnG1,
+    pub cap_r_2: BnG1,
+    pub z: BnScalar,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchablePartialEval {
+    pub index: usize,
+    pub value: BnG1,
+    pub proof: BatchableEvalProof,
+}
+
+impl BatchablePartialEval {
+    // the compact form (c, z) of the same proof, which is verified by PseudoRand.sol
+    pub fn compact(&self, domain: &impl HashToCurve, input: &[u8], vk: &BnG1) -> PartialEval {
+        self.compact_with_hash(&domain.hash_to_curve(input), vk)
+    }
+
+    fn compact_with_hash(&self, h: &BnG1, vk: &BnG1) -> PartialEval {
+        let g = BnG1::generator();
+        let c = eval_challenge(
+            &g,
+            h,
+            &self.proof.cap_r_1,
+            &self.proof.cap_r_2,
+            vk,
+            &self.value,
+        );
+
+        PartialEval {
+            index: self.index,
+            value: self.value,
+            proof: PartialEvalProof { z: self.proof.z, c },
+        }
+    }
+}
+
+// a batchable proof with its recomputed challenge and the verification key it is checked against
+struct BatchItem<'a> {
+    k: usize,
+    sigma: &'a BatchablePartialEval,
+    vk: BnG1,
+    c: BnScalar,
+}
+
+// check g^z = R1 * vk^c and h^z = R2 * v^c for all items at once: with random rho_k and rho'_k,
+// sum_k rho_k (z_k g - R1_k - c_k vk_k) + rho'_k (z_k h - R2_k - c_k v_k) is computed with a single
+// multi-scalar multiplication; it is zero for valid proofs and, except with negligible probability,
+// non-zero if any of the proofs is invalid
+fn combination_holds(g: &BnG1, h: &BnG1, items: &[BatchItem], mut rng: impl RngCore) -> bool {
+    let mut scalars = vec![BnScalar::zero(), BnScalar::zero()];
+    let mut bases = vec![*g, *h];
+    for item in items.iter() {
+        let rho_1 = BnScalar::random(&mut rng);
+        let rho_2 = BnScalar::random(&mut rng);
+        let z = item.sigma.proof.z;
+
+        scalars[0] += rho_1 * z;
+        scalars[1] += rho_2 * z;
+        scalars.extend([-rho_1, -rho_1 * item.c, -rho_2, -rho_2 * item.c]);
+        bases.extend([
+            item.sigma.proof.cap_r_1,
+            item.vk,
+            item.sigma.proof.cap_r_2,
+            item.sigma.value,
+        ]);
+    }
+
+    bool::from(best_multiexp(&scalars, &bases).is_identity())
+}
+
+// positions of the invalid proofs among the items; a failing combination is split in halves
+// until the invalid proofs are isolated
+fn bisect_invalid(g: &BnG1, h: &BnG1, items: &[BatchItem], rng: &mut impl RngCore) -> Vec<usize> {
+    if items.is_empty() || combination_holds(g, h, items, &mut *rng) {
+        return vec![];
+    }
+    if items.len() == 1 {
+        return vec![items[0].k];
+    }
+
+    let (left, right) = items.split_at(items.len() / 2);
+    let mut invalid = bisect_invalid(g, h, left, rng);
+    invalid.extend(bisect_invalid(g, h, right, rng));
+    invalid
+}
+
+// verify batchable partial evaluations on the same input against the verification keys of all
+// members with a random linear combination; if it fails, the invalid evaluations are found by
+// bisection and their indices are returned as an error
+pub fn verify_batchable_evaluations(
+    dkg_config: &DkgConfig,
+    domain: &impl HashToCurve,
+    input: &[u8],
+    sigmas: &[BatchablePartialEval],
+    vks: &[BnG1],
+    mut rng: impl RngCore,
+) -> Result<(), Error> {
+    let h = domain.hash_to_curve(input);
+    let g = BnG1::generator();
+
+    let mut invalid = vec![];
+    let mut items = vec![];
+    for (k, sigma) in sigmas.iter().enumerate() {
+        if sigma.index < 1 || sigma.index > dkg_config.number_of_members || sigma.index > vks.len()
+        {
+            invalid.push(k);
+            continue;
+        }
+
+        let vk = vks[sigma.index - 1];
+        let c = eval_challenge(
+            &g,
+            &h,
+            &sigma.proof.cap_r_1,
+            &sigma.proof.cap_r_2,
+            &vk,
+            &sigma.value,
+        );
+        items.push(BatchItem { k, sigma, vk, c });
+    }
+    invalid.extend(bisect_invalid(&g, &h, &items, &mut rng));
+
+    if !invalid.is_empty() {
+        let mut indices: Vec<_> = invalid.iter().map(|&k| sigmas[k].index).collect();
+        indices.sort();
+        return Err(Error::InvalidEvaluations { indices });
+    }
+
+    Ok(())
+}
+
+// positions of the partial evaluations on the same input that fail the proof check;
+// evaluations with an index out of range are considered invalid.
+// The proofs are in the compact form (c, z) verified by PseudoRand.sol, so R1 = g^z * vk^(-c) and
+// R2 = h^z * v^(-c) have to be recomputed for every proof before its challenge can be checked, and
+// each proof is checked on its own. Only the hash to curve of the input and the normalisation of
+// all R1, R2 are shared; evaluations in the batchable form are checked together with
+// verify_batchable_evaluations.
+fn invalid_evaluations(
+    dkg_config: &DkgConfig,
+    domain: &impl HashToCurve,
+    input: &[u8],
+    sigmas: &[PartialEval],
+    vks: &[BnG1],
+) -> Vec<usize> {
+    let h = domain.hash_to_curve(input);
+    let g = BnG1::generator();
+
+    let mut invalid = vec![];
+    let mut checked = vec![];
+    let mut cap_rs = vec![];
+    for (k, sigma) in sigmas.iter().enumerate() {
+        if sigma.index < 1 || sigma.index > dkg_config.number_of_members || sigma.index > vks.len()
+        {
+            invalid.push(k);
+            continue;
+        }
+
+        let vk = vks[sigma.index - 1];
+        let z = sigma.proof.z;
+        let c = sigma.proof.c;
+        cap_rs.push((g * z) - (vk * c));
+        cap_rs.push((h * z) - (sigma.value * c));
+        checked.push((k, vk));
+    }
+
+    let mut cap_rs_affine = vec![BnG1::identity(); cap_rs.len()];
+    BnG1Curve::batch_normalize(&cap_rs, &mut cap_rs_affine);
+
+    for ((k, vk), cap_r) in checked.iter().zip(cap_rs_affine.chunks(2)) {
+        let sigma = &sigmas[*k];
+        let c_tilde = eval_challenge(&g, &h, &cap_r[0], &cap_r[1], vk, &sigma.value);
+        if sigma.proof.c != c_tilde {
+            invalid.push(*k);
+        }
+    }
+
+    invalid.sort();
+    invalid
+}
+
+// verify partial evaluations on the same input against the verification keys of all members;
+// returns the indices of the invalid partial evaluations as an error
+pub fn verify_partial_evaluations(
+    dkg_config: &DkgConfig,
+    domain: &impl HashToCurve,
+    input: &[u8],
+    sigmas: &[PartialEval],
+    vks: &[BnG1],
+) -> Result<(), Error> {
+    let invalid = invalid_evaluations(dkg_config, domain, input, sigmas, vks);
+    if !invalid.is_empty() {
+        let mut indices: Vec<_> = invalid.iter().map(|&k| sigmas[k].index).collect();
+        indices.sort();
+        return Err(Error::InvalidEvaluations { indices });
+    }
+
+    Ok(())
+}
+
 pub struct PseudoRandom {
     pub proof: BnG1,
     pub value: Vec<u8>,
 }
 
 // check if the indices are in the range and sorted
-fn check_indices(number_of_members: usize, indices: &[usize]) -> Result<(), Error> {
+pub(crate) fn check_indices(number_of_members: usize, indices: &[usize]) -> Result<(), Error> {
     for i in 0..indices.len() {
         if i < indices.len() - 1 {
             if indices[i] >= indices[i + 1] {
@@ -242,41 +660,43 @@ fn check_indices(number_of_members: usize, indices: &[usize]) -> Result<(), Erro
     Ok(())
 }
 
+// compute Lagrange coefficients for interpolating at 0 from distinct indices
+pub(crate) fn lagrange_coefficients(indices: &[usize]) -> Vec<BnScalar> {
+    let indices: Vec<_> = indices.iter().map(|i| BnScalar::from(*i as u64)).collect();
+
+    // lambda_i = prod_k x_k / (x_i * prod_{k != i} (x_k - x_i))
+    let numerator = indices.iter().fold(BnScalar::one(), |acc, k| acc * k);
+    let mut denominators: Vec<_> = indices
+        .iter()
+        .map(|i| {
+            indices
+                .iter()
+                .filter(|k| !k.eq(&i))
+                .fold(*i, |acc, k| acc * (k - i))
+        })
+        .collect();
+
+    // invert all denominators with a single inversion; they are non-zero for distinct indices
+    denominators.iter_mut().batch_invert();
+
+    denominators.iter().map(|d| numerator * d).collect()
+}
+
 // obtain final random
 pub fn combine_partial_evaluations(
     dkg_config: &DkgConfig,
     sigmas: &[PartialEval],
 ) -> Result<PseudoRandom, Error> {
-    assert_eq!(sigmas.len(), dkg_config.threshold);
+    check_length(dkg_config.threshold, sigmas.len())?;
 
     let indices: Vec<_> = sigmas.iter().map(|sigma| sigma.index).collect();
     check_indices(dkg_config.number_of_members, &indices)?;
 
-    // compute Lagrange coefficients
-    let indices: Vec<_> = indices.iter().map(|i| BnScalar::from(*i as u64)).collect();
-    let mut lambdas = vec![];
-    for i in indices.iter() {
-        let mut numerator = BnScalar::one();
-        let mut denominator = BnScalar::one();
-        for k in indices.iter() {
-            if !k.eq(i) {
-                numerator = numerator * k;
-                denominator = denominator * (k - i);
-            }
-        }
-        let lambda = numerator * denominator.invert().expect("cannot divide zero");
-        lambdas.push(lambda);
-    }
+    let lambdas = lagrange_coefficients(&indices);
 
-    // compute pi
-    let pis: Vec<_> = sigmas
-        .iter()
-        .zip(lambdas.iter())
-        .map(|(sigma, lambda)| sigma.value * lambda)
-        .collect();
-    let sum = pis.iter().skip(1).fold(pis[0], |sum, p| sum + p);
-
-    let proof = sum.to_affine();
+    // compute pi = sum lambda_i * sigma_i with a multi-scalar multiplication
+    let values: Vec<_> = sigmas.iter().map(|sigma| sigma.value).collect();
+    let proof = best_multiexp(&lambdas, &values).to_affine();
 
     // reverse order to match solidity version
     let mut bytes = proof.y.to_bytes().to_vec();
@@ -288,6 +708,87 @@ pub fn combine_partial_evaluations(
     Ok(PseudoRandom { proof, value })
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectionReason {
+    // the index is not between 1...NUMBER_OF_MEMBERS
+    InvalidIndex,
+    // another valid evaluation with the same index has already been accepted
+    Duplicate,
+    // the proof does not verify against the member's verification key
+    InvalidProof,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RejectedEvaluation {
+    pub index: usize,
+    pub reason: RejectionReason,
+}
+
+pub struct CombinedEvaluations {
+    pub pseudo_random: PseudoRandom,
+    // indices of the partial evaluations used for interpolation, sorted
+    pub indices: Vec<usize>,
+    pub rejected: Vec<RejectedEvaluation>,
+}
+
+// obtain final random from any number of partial evaluations in any order;
+// if vks is given, every partial evaluation is verified before it is accepted
+pub fn combine_partial_evaluations_robust(
+    dkg_config: &DkgConfig,
+    domain: &impl HashToCurve,
+    input: &[u8],
+    sigmas: &[PartialEval],
+    vks: Option<&[BnG1]>,
+) -> Result<CombinedEvaluations, Error> {
+    let invalid = match vks {
+        Some(vks) => invalid_evaluations(dkg_config, domain, input, sigmas, vks),
+        None => vec![],
+    };
+
+    let mut rejected = vec![];
+    let mut accepted: Vec<&PartialEval> = vec![];
+    for (k, sigma) in sigmas.iter().enumerate() {
+        let reason = if sigma.index < 1 || sigma.index > dkg_config.number_of_members {
+            Some(RejectionReason::InvalidIndex)
+        } else if invalid.contains(&k) {
+            Some(RejectionReason::InvalidProof)
+        } else if accepted.iter().any(|s| s.index == sigma.index) {
+            Some(RejectionReason::Duplicate)
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => rejected.push(RejectedEvaluation {
+                index: sigma.index,
+                reason,
+            }),
+            None => accepted.push(sigma),
+        }
+    }
+
+    if accepted.len() < dkg_config.threshold {
+        return Err(Error::NotEnoughEvaluations {
+            valid: accepted.len(),
+            required: dkg_config.threshold,
+        });
+    }
+
+    accepted.sort_by_key(|s| s.index);
+    let selected: Vec<_> = accepted
+        .into_iter()
+        .take(dkg_config.threshold)
+        .cloned()
+        .collect();
+    let pseudo_random = combine_partial_evaluations(dkg_config, &selected)?;
+
+    Ok(CombinedEvaluations {
+        pseudo_random,
+        indices: selected.iter().map(|s| s.index).collect(),
+        rejected,
+    })
+}
+
 impl PseudoRandom {
     pub fn new(proof: BnG1, value: Vec<u8>) -> Self {
         Self { proof, value }
@@ -301,11 +802,10 @@ impl PseudoRandom {
         &self.proof
     }
 
-    pub fn verify(&self, input: &[u8], gpk: &BnG2) -> Result<(), Error> {
+    pub fn verify(&self, domain: &impl HashToCurve, input: &[u8], gpk: &BnG2) -> Result<(), Error> {
         let g2 = BnG2::generator();
 
-        let hasher = hash_to_curve_bn(EVAL_PREFIX);
-        let h: BnG1 = hasher(input).to_affine();
+        let h = domain.hash_to_curve(input);
 
         let gpk_prepared = G2Prepared::from_affine(gpk.clone());
         let g2_prepared = G2Prepared::from_affine(g2);
@@ -340,6 +840,87 @@ pub fn keygen(mut rng: impl RngCore) -> (BnScalar, BnG1) {
     (sk, pk)
 }
 
+// SCRAPE low-degree test: values g^{p(x_0)}, ..., g^{p(x_m)} lie on a polynomial p of degree less
+// than threshold iff sum_i v_i f(x_i) g^{p(x_i)} = 1 for every f of degree at most m - threshold,
+// where v_i = 1 / prod_{j != i} (x_i - x_j). A single random f costs one multi-exponentiation
+// and accepts values of higher degree with probability at most 1/r
+fn is_low_degree(
+    xs: &[BnScalar],
+    values: &[BnG1],
+    threshold: usize,
+    mut rng: impl RngCore,
+) -> bool {
+    assert_eq!(xs.len(), values.len());
+    // any m + 1 <= threshold values are on a polynomial of degree less than threshold
+    if xs.len() <= threshold {
+        return true;
+    }
+
+    let mut vs: Vec<_> = xs
+        .iter()
+        .map(|xi| {
+            xs.iter()
+                .filter(|xj| !xj.eq(&xi))
+                .fold(BnScalar::one(), |acc, xj| acc * (xi - xj))
+        })
+        .collect();
+    // the points are distinct so every product is non-zero
+    vs.iter_mut().batch_invert();
+
+    let f: Vec<_> = (0..xs.len() - threshold)
+        .map(|_| BnScalar::random(&mut rng))
+        .collect();
+    let coeffs: Vec<_> = xs
+        .iter()
+        .zip(vs.iter())
+        .map(|(x, v)| {
+            let fx = f.iter().rev().fold(BnScalar::zero(), |acc, a| acc * x + a);
+            fx * v
+        })
+        .collect();
+
+    bool::from(best_multiexp(&coeffs, values).is_identity())
+}
+
+// check that ga and the public shares g^{s_1}, ..., g^{s_n} are evaluations at 0, 1, ..., n
+// of a polynomial of degree less than threshold
+pub fn check_public_shares(
+    dkg_config: &DkgConfig,
+    ga: &BnG1,
+    public_shares: &[BnG1],
+    rng: impl RngCore,
+) -> Result<(), Error> {
+    check_length(dkg_config.number_of_members(), public_shares.len())?;
+
+    let xs: Vec<_> = (0..=public_shares.len())
+        .map(|i| BnScalar::from(i as u64))
+        .collect();
+    let mut values = vec![*ga];
+    values.extend_from_slice(public_shares);
+
+    if !is_low_degree(&xs, &values, dkg_config.threshold(), rng) {
+        return Err(Error::InvalidDegree);
+    }
+
+    Ok(())
+}
+
+// the same test on verification keys alone, e.g. vks.json without the global ga
+pub fn check_verify_keys(
+    dkg_config: &DkgConfig,
+    vks: &[BnG1],
+    rng: impl RngCore,
+) -> Result<(), Error> {
+    check_length(dkg_config.number_of_members(), vks.len())?;
+
+    let xs: Vec<_> = (1..=vks.len()).map(|i| BnScalar::from(i as u64)).collect();
+    if !is_low_degree(&xs, vks, dkg_config.threshold(), rng) {
+        return Err(Error::InvalidDegree);
+    }
+
+    Ok(())
+}
+
 // check if ga and g2a have the same exponent a
 pub fn is_dl_equal(ga: &BnG1, g2a: &BnG2) -> Result<(), Error> {
     let g = BnG1::generator();
@@ -371,14 +952,349 @@ mod tests {
         let dkg_config = DkgConfig::new(9, 16).unwrap(); // can be any numbers here
         let index = 1;
         let (sk, vk) = keygen(&mut rng);
-        let key = DkgShareKey { index, sk, vk };
+        let key = DkgShareKey::new(index, sk, vk);
         let x = b"the first random 20230626";
 
         let start = start_timer!(|| format!("partial evaluations {:?}", dkg_config));
-        let sigma = key.evaluate(x, &mut rng);
+        let sigma = key.evaluate(&EvalDomain::default(), x, &mut rng);
         end_timer!(start);
 
-        sigma.verify(&dkg_config, x, &vk).unwrap();
+        sigma
+            .verify(&dkg_config, &EvalDomain::default(), x, &vk)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_deterministic_evaluation() {
+        let mut rng = OsRng;
+        let dkg_config = DkgConfig::new(9, 16).unwrap();
+        let (sk, vk) = keygen(&mut rng);
+        let key = DkgShareKey::new(1, sk, vk);
+        let x = b"the first random 20230626";
+
+        let sigma1 = key.evaluate_deterministic(&EvalDomain::default(), x);
+        let sigma2 = key.evaluate_deterministic(&EvalDomain::default(), x);
+        sigma1
+            .verify(&dkg_config, &EvalDomain::default(), x, &vk)
+            .unwrap();
+        assert_eq!(sigma1.proof.z, sigma2.proof.z);
+        assert_eq!(sigma1.proof.c, sigma2.proof.c);
+
+        // the nonce depends on the input
+        let sigma3 =
+            key.evaluate_deterministic(&EvalDomain::default(), b"the second random 20230626");
+        assert_ne!(sigma1.proof.z, sigma3.proof.z);
+
+        // a broken rng repeating its output does not repeat the nonce for different inputs
+        let mut broken = ChaCha20Rng::seed_from_u64(42);
+        let sigma4 = key.evaluate_hedged(&EvalDomain::default(), x, &mut broken);
+        sigma4
+            .verify(&dkg_config, &EvalDomain::default(), x, &vk)
+            .unwrap();
+        assert_eq!(sigma4.value, sigma1.value);
+        assert_ne!(sigma4.proof.z, sigma1.proof.z);
+
+        let mut broken = ChaCha20Rng::seed_from_u64(42);
+        let sigma5 = key.evaluate_hedged(
+            &EvalDomain::default(),
+            b"the second random 20230626",
+            &mut broken,
+        );
+        assert_ne!(
+            sigma5.proof.z - sigma5.proof.c * sk,
+            sigma4.proof.z - sigma4.proof.c * sk
+        );
+    }
+
+    #[test]
+    fn test_bits2int() {
+        // the leftmost 254 bits, so the result is always below 2^254
+        assert_eq!(bits2int(&[0xff; 32])[0], 0x3f);
+        assert!(bits2int(&[0xff; 32])[1..].iter().all(|&b| b == 0xff));
+
+        let mut b = [0u8; 32];
+        b[0] = 0x80;
+        b[30] = 0x01;
+        b[31] = 0x07;
+        let mut expected = [0u8; 32];
+        expected[0] = 0x20;
+        expected[31] = 0x41;
+        assert_eq!(bits2int(&b), expected);
+    }
+
+    #[test]
+    fn test_verify_partial_evaluations() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(5, 9).unwrap();
+        let g = BnG1::generator();
+        let coeffs: Vec<_> = (0..dkg_config.threshold())
+            .map(|_| BnScalar::random(&mut rng))
+            .collect();
+        let keys: Vec<_> = shares(dkg_config.number_of_members(), &coeffs)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| DkgShareKey::new(i + 1, *s, (g * s).to_affine()))
+            .collect();
+        let vks: Vec<_> = keys.iter().map(|key| key.vk).collect();
+
+        let input = b"verify partial evaluations";
+        let mut evals: Vec<_> = keys
+            .iter()
+            .map(|key| key.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+
+        let start = start_timer!(|| format!("verify partial evaluations {:?}", dkg_config));
+        verify_partial_evaluations(&dkg_config, &EvalDomain::default(), input, &evals, &vks)
+            .unwrap();
+        end_timer!(start);
+
+        // corrupt the value of member 3, the proof of member 7 and the index of member 8
+        evals[2].value = (evals[2].value + g).to_affine();
+        evals[6].proof.z += BnScalar::one();
+        evals[7].index = 10;
+
+        let res =
+            verify_partial_evaluations(&dkg_config, &EvalDomain::default(), input, &evals, &vks);
+        assert!(
+            matches!(res, Err(Error::InvalidEvaluations { indices }) if indices == vec![3, 7, 10])
+        );
+
+        for e in evals.iter().filter(|e| ![3, 7, 10].contains(&e.index)) {
+            e.verify(
+                &dkg_config,
+                &EvalDomain::default(),
+                input,
+                &vks[e.index - 1],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_verify_batchable_evaluations() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(5, 9).unwrap();
+        let g = BnG1::generator();
+        let coeffs: Vec<_> = (0..dkg_config.threshold())
+            .map(|_| BnScalar::random(&mut rng))
+            .collect();
+        let keys: Vec<_> = shares(dkg_config.number_of_members(), &coeffs)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| DkgShareKey::new(i + 1, *s, (g * s).to_affine()))
+            .collect();
+        let vks: Vec<_> = keys.iter().map(|key| key.vk).collect();
+
+        let domain = EvalDomain::default();
+        let input = b"batch verification";
+        let mut evals: Vec<_> = keys
+            .iter()
+            .map(|key| key.evaluate_batchable(&domain, input, &mut rng))
+            .collect();
+
+        let start = start_timer!(|| format!("batch verify partial evaluations {:?}", dkg_config));
+        verify_batchable_evaluations(&dkg_config, &domain, input, &evals, &vks, &mut rng).unwrap();
+        end_timer!(start);
+
+        // the compact form of the same proofs is accepted by the single verification
+        for e in evals.iter() {
+            let compact = e.compact(&domain, input, &vks[e.index - 1]);
+            compact
+                .verify(&dkg_config, &domain, input, &vks[e.index - 1])
+                .unwrap();
+            assert_eq!(compact.value, e.value);
+        }
+
+        // corrupt the value of member 2, R1 of member 3, the proof of member 7 and the index of
+        // member 8; the bisection has to separate invalid evaluations in the same half
+        evals[1].value = (evals[1].value + g).to_affine();
+        evals[2].proof.cap_r_1 = (evals[2].proof.cap_r_1 + g).to_affine();
+        evals[6].proof.z += BnScalar::one();
+        evals[7].index = 10;
+
+        let res = verify_batchable_evaluations(&dkg_config, &domain, input, &evals, &vks, &mut rng);
+        assert!(
+            matches!(res, Err(Error::InvalidEvaluations { indices }) if indices == vec![2, 3, 7, 10])
+        );
+
+        let valid: Vec<_> = evals
+            .into_iter()
+            .filter(|e| ![2, 3, 7, 10].contains(&e.index))
+            .collect();
+        verify_batchable_evaluations(&dkg_config, &domain, input, &valid, &vks, &mut rng).unwrap();
+        assert!(verify_batchable_evaluations(
+            &dkg_config,
+            &domain,
+            b"other input",
+            &valid,
+            &vks,
+            &mut rng
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_combine_robust() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(4, 7).unwrap();
+        let g = BnG1::generator();
+        let g2 = BnG2::generator();
+        let coeffs: Vec<_> = (0..dkg_config.threshold())
+            .map(|_| BnScalar::random(&mut rng))
+            .collect();
+        let keys: Vec<_> = shares(dkg_config.number_of_members(), &coeffs)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| DkgShareKey::new(i + 1, *s, (g * s).to_affine()))
+            .collect();
+        let vks: Vec<_> = keys.iter().map(|key| key.vk).collect();
+        let gpk = (g2 * coeffs[0]).to_affine();
+
+        let input = b"robust combine";
+        let evals: Vec<_> = keys
+            .iter()
+            .map(|key| key.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        let expected = combine_partial_evaluations(&dkg_config, &evals[0..4]).unwrap();
+
+        // unsorted, with a duplicate, an invalid proof and an invalid index
+        let mut bad = evals[1].clone();
+        bad.value = (bad.value + g).to_affine();
+        let mut out_of_range = evals[0].clone();
+        out_of_range.index = 8;
+        let sigmas = vec![
+            evals[6].clone(),
+            bad,
+            evals[3].clone(),
+            evals[3].clone(),
+            out_of_range,
+            evals[2].clone(),
+            evals[4].clone(),
+            evals[1].clone(),
+        ];
+
+        let combined = combine_partial_evaluations_robust(
+            &dkg_config,
+            &EvalDomain::default(),
+            input,
+            &sigmas,
+            Some(&vks),
+        )
+        .unwrap();
+        combined
+            .pseudo_random
+            .verify(&EvalDomain::default(), input, &gpk)
+            .unwrap();
+        assert_eq!(combined.pseudo_random.value, expected.value);
+        assert_eq!(combined.indices, vec![2, 3, 4, 5]);
+        assert_eq!(
+            combined.rejected,
+            vec![
+                RejectedEvaluation {
+                    index: 2,
+                    reason: RejectionReason::InvalidProof
+                },
+                RejectedEvaluation {
+                    index: 4,
+                    reason: RejectionReason::Duplicate
+                },
+                RejectedEvaluation {
+                    index: 8,
+                    reason: RejectionReason::InvalidIndex
+                },
+            ]
+        );
+
+        // without verification the invalid evaluation may be selected
+        let combined = combine_partial_evaluations_robust(
+            &dkg_config,
+            &EvalDomain::default(),
+            input,
+            &sigmas,
+            None,
+        )
+        .unwrap();
+        assert!(combined
+            .pseudo_random
+            .verify(&EvalDomain::default(), input, &gpk)
+            .is_err());
+
+        let res = combine_partial_evaluations_robust(
+            &dkg_config,
+            &EvalDomain::default(),
+            input,
+            &sigmas[0..4],
+            Some(&vks),
+        );
+        assert!(matches!(
+            res,
+            Err(Error::NotEnoughEvaluations {
+                valid: 2,
+                required: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn test_eval_domain() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(2, 3).unwrap();
+        let g = BnG1::generator();
+        let g2 = BnG2::generator();
+        let coeffs: Vec<_> = (0..dkg_config.threshold())
+            .map(|_| BnScalar::random(&mut rng))
+            .collect();
+        let keys: Vec<_> = shares(dkg_config.number_of_members(), &coeffs)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| DkgShareKey::new(i + 1, *s, (g * s).to_affine()))
+            .collect();
+        let vks: Vec<_> = keys.iter().map(|key| key.vk).collect();
+        let gpk = (g2 * coeffs[0]).to_affine();
+
+        assert_eq!(EvalDomain::default().prefix(), EVAL_PREFIX);
+        let domain1 = EvalDomain::new(1, &[0x11; 20], "lottery");
+        let domain2 = EvalDomain::new(288, &[0x11; 20], "lottery");
+        assert_ne!(domain1, domain2);
+
+        let input = b"domain separation";
+        let evals: Vec<_> = keys[0..2]
+            .iter()
+            .map(|key| key.evaluate(&domain1, input, &mut rng))
+            .collect();
+        for (e, vk) in evals.iter().zip(vks.iter()) {
+            e.verify(&dkg_config, &domain1, input, vk).unwrap();
+            assert!(e.verify(&dkg_config, &domain2, input, vk).is_err());
+            assert!(e
+                .verify(&dkg_config, &EvalDomain::default(), input, vk)
+                .is_err());
+        }
+        verify_partial_evaluations(&dkg_config, &domain1, input, &evals, &vks).unwrap();
+
+        let combined =
+            combine_partial_evaluations_robust(&dkg_config, &domain1, input, &evals, Some(&vks))
+                .unwrap();
+        combined
+            .pseudo_random
+            .verify(&domain1, input, &gpk)
+            .unwrap();
+        assert!(combined
+            .pseudo_random
+            .verify(&EvalDomain::default(), input, &gpk)
+            .is_err());
+
+        // the default domain gives a different pseudorandom value
+        let evals: Vec<_> = keys[0..2]
+            .iter()
+            .map(|key| key.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        let v = combine_partial_evaluations(&dkg_config, &evals).unwrap();
+        v.verify(&EvalDomain::default(), input, &gpk).unwrap();
+        assert_ne!(v.value, combined.pseudo_random.value);
     }
 
     fn pseudo_random(threshold: usize, number_of_members: usize) {
@@ -396,11 +1312,7 @@ mod tests {
         let keys: Vec<_> = shares
             .iter()
             .enumerate()
-            .map(|(i, s)| DkgShareKey {
-                index: i + 1,
-                sk: *s,
-                vk: (g * s).to_affine(),
-            })
+            .map(|(i, s)| DkgShareKey::new(i + 1, *s, (g * s).to_affine()))
             .collect();
         let vks: Vec<_> = keys.iter().map(|key| key.vk).collect();
 
@@ -409,13 +1321,13 @@ mod tests {
 
         let evals: Vec<_> = keys
             .iter()
-            .map(|key| key.evaluate(input, &mut rng))
+            .map(|key| key.evaluate(&EvalDomain::default(), input, &mut rng))
             .collect();
 
-        let res = evals
-            .iter()
-            .zip(vks.iter())
-            .all(|(e, vk)| e.verify(&dkg_config, input, vk).is_ok());
+        let res = evals.iter().zip(vks.iter()).all(|(e, vk)| {
+            e.verify(&dkg_config, &EvalDomain::default(), input, vk)
+                .is_ok()
+        });
 
         assert!(res);
 
@@ -425,10 +1337,55 @@ mod tests {
         end_timer!(start);
 
         let start = start_timer!(|| format!("verify pseudo random value {:?}", dkg_config));
-        pseudo_random.verify(input, &gpk).unwrap();
+        pseudo_random
+            .verify(&EvalDomain::default(), input, &gpk)
+            .unwrap();
         end_timer!(start);
     }
 
+    #[test]
+    fn test_check_public_shares() {
+        let mut rng = OsRng;
+        let dkg_config = DkgConfig::new(3, 6).unwrap();
+        let coeffs: Vec<_> = (0..3).map(|_| BnScalar::random(&mut rng)).collect();
+        let g = BnG1::generator();
+        let ga = (g * coeffs[0]).to_affine();
+        let public_shares: Vec<_> = shares(6, &coeffs)
+            .iter()
+            .map(|s| (g * s).to_affine())
+            .collect();
+
+        check_public_shares(&dkg_config, &ga, &public_shares, &mut rng).unwrap();
+        check_verify_keys(&dkg_config, &public_shares, &mut rng).unwrap();
+
+        // a wrong constant term
+        let gb = (g * (coeffs[0] + BnScalar::one())).to_affine();
+        assert!(matches!(
+            check_public_shares(&dkg_config, &gb, &public_shares, &mut rng),
+            Err(Error::InvalidDegree)
+        ));
+
+        // a single corrupted share
+        let mut corrupted = public_shares.clone();
+        corrupted[4] = (corrupted[4] + g).to_affine();
+        assert!(check_public_shares(&dkg_config, &ga, &corrupted, &mut rng).is_err());
+        assert!(check_verify_keys(&dkg_config, &corrupted, &mut rng).is_err());
+
+        // shares of a polynomial with degree threshold
+        let mut high: Vec<_> = coeffs.clone();
+        high.push(BnScalar::random(&mut rng));
+        let high_shares: Vec<_> = shares(6, &high)
+            .iter()
+            .map(|s| (g * s).to_affine())
+            .collect();
+        assert!(check_verify_keys(&dkg_config, &high_shares, &mut rng).is_err());
+
+        assert!(matches!(
+            check_verify_keys(&dkg_config, &public_shares[0..5], &mut rng),
+            Err(Error::LengthMismatch { .. })
+        ));
+    }
+
     #[test]
     fn test_pseudo_random() {
         pseudo_random(4, 6);
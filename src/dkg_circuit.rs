@@ -1,3 +1,4 @@
+use crate::compressed::PublicData;
 use crate::dkg::DkgConfig;
 #[cfg(feature = "g2chip")]
 use crate::ecc_chip::FixedPoint2Chip;
@@ -14,7 +15,8 @@ use halo2_ecc::integer::IntegerConfig;
 use halo2_ecc::maingate::RegionCtx;
 use halo2_ecc::EccConfig;
 use halo2_gadgets::poseidon::{
-    primitives::ConstantLength, Hash as PoseidonHash, Pow5Chip, Pow5Config,
+    primitives::{ConstantLength, Domain},
+    Hash as PoseidonHash, PaddedWord, Pow5Chip, Pow5Config, Sponge,
 };
 use halo2_maingate::{
     MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig, RangeInstructions,
@@ -100,6 +102,25 @@ impl DkgCircuitConfig {
     }
 }
 
+// the statements proven with DkgCircuit; the kinds lay out different circuits,
+// so each of them needs its own proving and verifying key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitKind {
+    Dkg,
+    Compressed,
+    Refresh,
+}
+
+impl CircuitKind {
+    pub fn instance_size(&self, dkg_config: &DkgConfig) -> usize {
+        match self {
+            CircuitKind::Dkg => dkg_config.instance_size(),
+            CircuitKind::Compressed => 1,
+            CircuitKind::Refresh => dkg_config.refresh_instance_size(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct DkgCircuit {
     dkg_config: DkgConfig,
@@ -107,6 +128,13 @@ pub struct DkgCircuit {
     random: Value<BnScalar>,
     public_keys: Vec<Value<GkG1>>,
     grumpkin_aux_generator: Value<GkG1>,
+    // in refresh mode the constant term is constrained to zero and g^a, g2^a are not exposed
+    refresh: bool,
+    // in compressed mode the only public input is a poseidon commitment to the public data
+    compressed: bool,
+    // assign the bits of r + p instead of the canonical bits of r
+    #[cfg(test)]
+    pub(crate) non_canonical_random: bool,
 }
 
 impl DkgCircuit {
@@ -126,9 +154,51 @@ impl DkgCircuit {
             random,
             public_keys,
             grumpkin_aux_generator,
+            refresh: false,
+            compressed: false,
+            #[cfg(test)]
+            non_canonical_random: false,
         }
     }
 
+    // circuit with the commitment of commit_public_data as the only public input
+    pub fn new_compressed(
+        dkg_config: DkgConfig,
+        coeffs: Vec<Value<BnScalar>>,
+        random: Value<BnScalar>,
+        public_keys: Vec<Value<GkG1>>,
+        grumpkin_aux_generator: Value<GkG1>,
+    ) -> Self {
+        let mut circuit = Self::new(
+            dkg_config,
+            coeffs,
+            random,
+            public_keys,
+            grumpkin_aux_generator,
+        );
+        circuit.compressed = true;
+        circuit
+    }
+
+    // circuit for dealing a polynomial with zero constant term to refresh the existing shares
+    pub fn new_refresh(
+        dkg_config: DkgConfig,
+        coeffs: Vec<Value<BnScalar>>,
+        random: Value<BnScalar>,
+        public_keys: Vec<Value<GkG1>>,
+        grumpkin_aux_generator: Value<GkG1>,
+    ) -> Self {
+        let mut circuit = Self::new(
+            dkg_config,
+            coeffs,
+            random,
+            public_keys,
+            grumpkin_aux_generator,
+        );
+        circuit.refresh = true;
+        circuit
+    }
+
     pub fn dummy(dkg_config: DkgConfig) -> Self {
         let coeffs: Vec<_> = (0..dkg_config.threshold())
             .map(|_| Value::unknown())
@@ -145,6 +215,30 @@ impl DkgCircuit {
             random,
             public_keys,
             grumpkin_aux_generator,
+            refresh: false,
+            compressed: false,
+            #[cfg(test)]
+            non_canonical_random: false,
+        }
+    }
+
+    pub fn dummy_compressed(dkg_config: DkgConfig) -> Self {
+        let mut circuit = Self::dummy(dkg_config);
+        circuit.compressed = true;
+        circuit
+    }
+
+    pub fn dummy_refresh(dkg_config: DkgConfig) -> Self {
+        let mut circuit = Self::dummy(dkg_config);
+        circuit.refresh = true;
+        circuit
+    }
+
+    pub fn dummy_of(dkg_config: DkgConfig, kind: CircuitKind) -> Self {
+        match kind {
+            CircuitKind::Dkg => Self::dummy(dkg_config),
+            CircuitKind::Compressed => Self::dummy_compressed(dkg_config),
+            CircuitKind::Refresh => Self::dummy_refresh(dkg_config),
         }
     }
 
@@ -203,6 +297,10 @@ impl Circuit<BnScalar> for DkgCircuit {
                     coeffs.push(a_assigned);
                 }
 
+                if self.refresh {
+                    main_gate.assert_zero(ctx, &coeffs[0])?;
+                }
+
                 let mut shares = vec![];
 
                 // compute s0
@@ -254,28 +352,36 @@ impl Circuit<BnScalar> for DkgCircuit {
             },
         )?;
 
-        let ga = layouter.assign_region(
-            || "region ecc mul g^a",
-            |region| {
-                let offset = 0;
-                let ctx = &mut RegionCtx::new(region, offset);
+        // public data in the order of the instance; the region offsets of the wrapping follow
+        // the instance offsets
+        let mut public = vec![];
+        // g^a is not exposed when refreshing since a = 0
+        let mut assigned_base = None;
+        if !self.refresh {
+            let ga = layouter.assign_region(
+                || "region ecc mul g^a",
+                |region| {
+                    let offset = 0;
+                    let ctx = &mut RegionCtx::new(region, offset);
 
-                let ga = fixed_chip.mul(ctx, &a)?;
-                // normalise for public inputs
-                let ga = fixed_chip.normalize(ctx, &ga)?;
+                    let ga = fixed_chip.mul(ctx, &a)?;
+                    // normalise for public inputs
+                    let ga = fixed_chip.normalize(ctx, &ga)?;
 
-                Ok(ga)
-            },
-        )?;
+                    Ok(ga)
+                },
+            )?;
 
-        let mut instance_offset = 0usize;
-        let assigned_base = fixed_chip.expose_public_optimal(
-            layouter.namespace(|| "bn256 G1 point g^a"),
-            ga,
-            WRAP_LEN,
-            None,
-            &mut instance_offset,
-        )?;
+            let (base, wrapped) = fixed_chip.wrap_public(
+                layouter.namespace(|| "bn256 G1 point g^a"),
+                ga,
+                WRAP_LEN,
+                None,
+                public.len(),
+            )?;
+            public.extend(wrapped);
+            assigned_base = Some(base);
+        }
 
         for i in 0..self.number_of_members() {
             let gs = layouter.assign_region(
@@ -292,38 +398,42 @@ impl Circuit<BnScalar> for DkgCircuit {
                 },
             )?;
 
-            fixed_chip.expose_public_optimal(
+            let (base, wrapped) = fixed_chip.wrap_public(
                 layouter.namespace(|| "bn256 G1 point g^s"),
                 gs,
                 WRAP_LEN,
-                Some(assigned_base.clone()),
-                &mut instance_offset,
+                assigned_base.clone(),
+                public.len(),
             )?;
+            public.extend(wrapped);
+            assigned_base = Some(base);
         }
 
         // compute g2^a
         #[cfg(feature = "g2chip")]
-        let g2a = layouter.assign_region(
-            || "region mul",
-            |region| {
-                let offset = 0;
-                let ctx = &mut RegionCtx::new(region, offset);
+        if !self.refresh {
+            let g2a = layouter.assign_region(
+                || "region mul",
+                |region| {
+                    let offset = 0;
+                    let ctx = &mut RegionCtx::new(region, offset);
 
-                let g2a = fixed2_chip.mul(ctx, &a)?;
-                let g2a = fixed2_chip.normalize(ctx, &g2a)?;
+                    let g2a = fixed2_chip.mul(ctx, &a)?;
+                    let g2a = fixed2_chip.normalize(ctx, &g2a)?;
 
-                Ok(g2a)
-            },
-        )?;
+                    Ok(g2a)
+                },
+            )?;
 
-        #[cfg(feature = "g2chip")]
-        fixed2_chip.expose_public_optimal(
-            layouter.namespace(|| "bn256 G2 point g2^a"),
-            g2a,
-            WRAP_LEN,
-            Some(assigned_base),
-            &mut instance_offset,
-        )?;
+            let (_, wrapped) = fixed2_chip.wrap_public(
+                layouter.namespace(|| "bn256 G2 point g2^a"),
+                g2a,
+                WRAP_LEN,
+                assigned_base,
+                public.len(),
+            )?;
+            public.extend(wrapped);
+        }
 
         let (bits, gr) = layouter.assign_region(
             || "region grumpkin ecc mul g^r",
@@ -333,8 +443,15 @@ impl Circuit<BnScalar> for DkgCircuit {
 
                 let g = grumpkin_chip.assign_constant(ctx, GkG1::generator())?;
 
-                // we don't care about the value of r; if r==0, add will fail
-                let bits = grumpkin_chip.to_bits_unsafe(ctx, &self.random)?;
+                // r is non-zero and its bits are canonical, so g^r and pk^r use r itself
+                #[cfg(not(test))]
+                let (_, bits) = grumpkin_chip.to_bits_canonical(ctx, &self.random)?;
+                #[cfg(test)]
+                let (_, bits) = if self.non_canonical_random {
+                    grumpkin_chip.to_bits_non_canonical(ctx, &self.random)?
+                } else {
+                    grumpkin_chip.to_bits_canonical(ctx, &self.random)?
+                };
                 // gr = g^r
                 let gr = grumpkin_chip.mul_bits(ctx, &g, &bits)?;
 
@@ -342,11 +459,8 @@ impl Circuit<BnScalar> for DkgCircuit {
             },
         )?;
 
-        grumpkin_chip.expose_public(
-            layouter.namespace(|| "cipher g^r"),
-            gr,
-            &mut instance_offset,
-        )?;
+        public.push(gr.x().clone());
+        public.push(gr.y().clone());
 
         let mut assigned_pks = vec![];
         for i in 0..self.number_of_members() {
@@ -388,16 +502,39 @@ impl Circuit<BnScalar> for DkgCircuit {
                 },
             )?;
 
-            main_gate.expose_public(
-                layouter.namespace(|| "cipher main"),
-                cipher,
-                instance_offset,
-            )?;
-            instance_offset += 1;
+            public.push(cipher);
         }
 
         for pk in assigned_pks.into_iter() {
-            grumpkin_chip.expose_public(layouter.namespace(|| "pk"), pk, &mut instance_offset)?;
+            public.push(pk.x().clone());
+            public.push(pk.y().clone());
+        }
+
+        if self.compressed {
+            // the sponge of commit_public_data, two elements of the public data per permutation
+            let poseidon_chip = Pow5Chip::construct(config.poseidon_config.clone());
+            let mut sponge =
+                Sponge::<_, _, P128Pow5T3Bn, _, PublicData, POSEIDON_WIDTH, POSEIDON_RATE>::new(
+                    poseidon_chip,
+                    layouter.namespace(|| "sponge init"),
+                )?;
+
+            let padding = PublicData::padding(public.len());
+            for x in public.into_iter() {
+                sponge.absorb(layouter.namespace(|| "absorb"), PaddedWord::Message(x))?;
+            }
+            for pad in padding.into_iter() {
+                sponge.absorb(layouter.namespace(|| "absorb"), PaddedWord::Padding(pad))?;
+            }
+            let commitment = sponge
+                .finish_absorbing(layouter.namespace(|| "finish absorbing"))?
+                .squeeze(layouter.namespace(|| "squeeze"))?;
+
+            main_gate.expose_public(layouter.namespace(|| "commitment"), commitment, 0)?;
+        } else {
+            for (i, x) in public.into_iter().enumerate() {
+                main_gate.expose_public(layouter.namespace(|| "public data"), x, i)?;
+            }
         }
 
         Ok(())
@@ -96,14 +96,22 @@ impl<
         Ok(())
     }
 
-    pub fn expose_public_optimal(
+    // wrap the limbs of the coordinates into public inputs without constraining the instance;
+    // offset is the region offset of the wrapping
+    pub fn wrap_public(
         &self,
         mut layouter: impl Layouter<C::ScalarExt>,
         point: AssignedPoint2<W, C::ScalarExt, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
         wrap_len: usize,
         assigned_base: Option<AssignedValue<C::ScalarExt>>,
-        offset: &mut usize,
-    ) -> Result<AssignedValue<C::ScalarExt>, PlonkError> {
+        offset: usize,
+    ) -> Result<
+        (
+            AssignedValue<C::ScalarExt>,
+            Vec<AssignedValue<C::ScalarExt>>,
+        ),
+        PlonkError,
+    > {
         assert!(BIT_LEN_LIMB < 128);
         assert!(BIT_LEN_LIMB * wrap_len < C::ScalarExt::NUM_BITS as usize);
         // for simplicity
@@ -115,7 +123,7 @@ impl<
         let (assigned_base, wrapped) = layouter.assign_region(
             || "region wrap up public inputs",
             |region| {
-                let ctx = &mut RegionCtx::new(region, *offset);
+                let ctx = &mut RegionCtx::new(region, offset);
 
                 let assigned_base = match &assigned_base {
                     Some(base) => base.clone(),
@@ -152,6 +160,26 @@ impl<
             },
         )?;
 
+        Ok((assigned_base, wrapped))
+    }
+
+    pub fn expose_public_optimal(
+        &self,
+        mut layouter: impl Layouter<C::ScalarExt>,
+        point: AssignedPoint2<W, C::ScalarExt, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        wrap_len: usize,
+        assigned_base: Option<AssignedValue<C::ScalarExt>>,
+        offset: &mut usize,
+    ) -> Result<AssignedValue<C::ScalarExt>, PlonkError> {
+        let main_gate = self.main_gate();
+        let (assigned_base, wrapped) = self.wrap_public(
+            layouter.namespace(|| "wrap"),
+            point,
+            wrap_len,
+            assigned_base,
+            *offset,
+        )?;
+
         for limb in wrapped.into_iter() {
             main_gate.expose_public(layouter.namespace(|| "G2 point coords"), limb, *offset)?;
             *offset += 1;
@@ -69,14 +69,16 @@ impl<C: CurveAffine + AuxGen, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB:
         Ok(())
     }
 
-    pub fn expose_public_optimal(
+    // wrap the limbs of the coordinates into public inputs without constraining the instance;
+    // offset is the region offset of the wrapping
+    pub fn wrap_public(
         &self,
         mut layouter: impl Layouter<C::Scalar>,
         point: AssignedPoint<C::Base, C::Scalar, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
         wrap_len: usize,
         assigned_base: Option<AssignedValue<C::Scalar>>,
-        offset: &mut usize,
-    ) -> Result<AssignedValue<C::Scalar>, PlonkError> {
+        offset: usize,
+    ) -> Result<(AssignedValue<C::Scalar>, Vec<AssignedValue<C::Scalar>>), PlonkError> {
         assert!(BIT_LEN_LIMB < 128);
         assert!(BIT_LEN_LIMB * wrap_len < C::Scalar::NUM_BITS as usize);
         // for simplicity
@@ -88,7 +90,7 @@ impl<C: CurveAffine + AuxGen, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB:
         let (assigned_base, wrapped) = layouter.assign_region(
             || "region wrap up public inputs",
             |region| {
-                let ctx = &mut RegionCtx::new(region, *offset);
+                let ctx = &mut RegionCtx::new(region, offset);
 
                 let assigned_base = match &assigned_base {
                     Some(base) => base.clone(),
@@ -120,6 +122,26 @@ impl<C: CurveAffine + AuxGen, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB:
             },
         )?;
 
+        Ok((assigned_base, wrapped))
+    }
+
+    pub fn expose_public_optimal(
+        &self,
+        mut layouter: impl Layouter<C::Scalar>,
+        point: AssignedPoint<C::Base, C::Scalar, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        wrap_len: usize,
+        assigned_base: Option<AssignedValue<C::Scalar>>,
+        offset: &mut usize,
+    ) -> Result<AssignedValue<C::Scalar>, PlonkError> {
+        let main_gate = self.main_gate();
+        let (assigned_base, wrapped) = self.wrap_public(
+            layouter.namespace(|| "wrap"),
+            point,
+            wrap_len,
+            assigned_base,
+            *offset,
+        )?;
+
         for limb in wrapped.into_iter() {
             main_gate.expose_public(layouter.namespace(|| "G point coords"), limb, *offset)?;
             *offset += 1;
@@ -12,6 +12,43 @@ pub enum Error {
     InvalidIndex { index: usize },
     #[error("invalid index order {index:?}")]
     InvalidOrder { index: usize },
+    #[error("invalid share from dealer for member {index:?}")]
+    InvalidShare { index: usize },
+    #[error("invalid complaint against dealer {dealer:?}")]
+    InvalidComplaint { dealer: usize },
+    #[error("invalid threshold {threshold:?} for weights {weights:?}")]
+    InvalidWeights {
+        threshold: usize,
+        weights: Vec<usize>,
+    },
+    #[error("invalid qualified policy with minimum {min_dealers:?} dealers")]
+    InvalidPolicy { min_dealers: usize },
+    #[error("not enough qualified dealers: {qualified:?} out of required {required:?}")]
+    NotEnoughDealers { qualified: usize, required: usize },
+    #[error("invalid partial evaluations from members {indices:?}")]
+    InvalidEvaluations { indices: Vec<usize> },
+    #[error("not enough valid partial evaluations: {valid:?} out of required {required:?}")]
+    NotEnoughEvaluations { valid: usize, required: usize },
+    #[error("cannot draw {k:?} out of {n:?}")]
+    InvalidRange { k: u64, n: u64 },
+    #[error("invalid encoding: {0}")]
+    InvalidEncoding(String),
+    #[error("invalid keystore: {0}")]
+    InvalidKeystore(String),
+    #[error("invalid keystore password")]
+    InvalidPassword,
+    #[error("malformed instance: {0}")]
+    MalformedInstance(String),
+    #[error("point is not on the curve or not in the prime order subgroup")]
+    InvalidPoint,
+    #[error("length mismatch: expected {expected:?}, got {actual:?}")]
+    LengthMismatch { expected: usize, actual: usize },
+    #[error("invalid proof")]
+    InvalidProof,
+    #[error("invalid proofs from dealers {dealers:?}")]
+    InvalidProofs { dealers: Vec<usize> },
+    #[error("shares are not on a polynomial of degree less than the threshold")]
+    InvalidDegree,
     #[error("verification failed")]
     VerifyFailed,
     #[error("circuit error {0:?}")]
@@ -0,0 +1,219 @@
+// expansion of the final pseudorandom value into bytes, integers and permutations;
+// every step is specified so that contracts/libs/RandomExpand.sol reproduces it exactly:
+//
+// - word_i = keccak256(value || uint256(i)) for i = 0, 1, 2, ..., with i encoded as 32 bytes big-endian,
+//   i.e. keccak256(abi.encodePacked(value, i)); the byte stream is word_0 || word_1 || ...
+// - uniform(n) reads words w as big-endian uint256 and returns w mod n for the first w >= 2^256 mod n;
+//   the accepted range is a multiple of n so the result has no modulo bias
+// - shuffle is Fisher-Yates: for i = len - 1 down to 1, swap a[i] and a[uniform(i + 1)]
+// - sample(k, n) is the first k steps of Fisher-Yates on 0..n: for i = 0..k,
+//   swap a[i] and a[i + uniform(n - i)], then return a[0..k]
+//
+// uniform, shuffle and sample on a stream continue from the words consumed by previous calls
+use crate::dkg::PseudoRandom;
+use crate::error::Error;
+use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
+
+pub const WORD_LEN: usize = 32;
+
+#[derive(Clone, Debug)]
+pub struct RandomStream {
+    value: Vec<u8>,
+    counter: u64,
+}
+
+impl RandomStream {
+    pub fn new(value: &[u8]) -> Self {
+        Self {
+            value: value.to_vec(),
+            counter: 0,
+        }
+    }
+
+    // number of words consumed so far, i.e. the counter of the next word
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    pub fn next_word(&mut self) -> [u8; WORD_LEN] {
+        let mut counter = [0u8; WORD_LEN];
+        counter[WORD_LEN - 8..].copy_from_slice(&self.counter.to_be_bytes());
+        self.counter += 1;
+
+        Keccak256::new()
+            .chain_update(&self.value)
+            .chain_update(counter)
+            .finalize()
+            .to_vec()
+            .try_into()
+            .unwrap()
+    }
+
+    // the next len bytes of the stream; a partly used word is discarded
+    pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(len + WORD_LEN);
+        while bytes.len() < len {
+            bytes.extend(self.next_word());
+        }
+        bytes.truncate(len);
+        bytes
+    }
+
+    // unbiased integer in 0..n
+    pub fn uniform(&mut self, n: u64) -> Result<u64, Error> {
+        if n == 0 {
+            return Err(Error::InvalidRange { k: 1, n });
+        }
+
+        let threshold = rejection_threshold(n);
+        loop {
+            let word = self.next_word();
+            if !word_less_than(&word, threshold) {
+                return Ok(word_mod(&word, n));
+            }
+        }
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            // i + 1 > 0 and items.len() fits into u64
+            let j = self.uniform(i as u64 + 1).unwrap() as usize;
+            items.swap(i, j);
+        }
+    }
+
+    // k distinct integers from 0..n in the order they were drawn
+    pub fn sample(&mut self, k: u64, n: u64) -> Result<Vec<u64>, Error> {
+        if k > n {
+            return Err(Error::InvalidRange { k, n });
+        }
+
+        // a sparse array: positions that have not been swapped hold their own index
+        let mut swapped: HashMap<u64, u64> = HashMap::new();
+        let mut sampled = Vec::with_capacity(k as usize);
+        for i in 0..k {
+            let j = i + self.uniform(n - i)?;
+            let a_i = *swapped.get(&i).unwrap_or(&i);
+            let a_j = *swapped.get(&j).unwrap_or(&j);
+            swapped.insert(j, a_i);
+            sampled.push(a_j);
+        }
+
+        Ok(sampled)
+    }
+}
+
+impl PseudoRandom {
+    pub fn stream(&self) -> RandomStream {
+        RandomStream::new(&self.value)
+    }
+
+    pub fn expand(&self, len: usize) -> Vec<u8> {
+        self.stream().bytes(len)
+    }
+
+    pub fn uniform(&self, n: u64) -> Result<u64, Error> {
+        self.stream().uniform(n)
+    }
+
+    pub fn shuffle<T>(&self, items: &mut [T]) {
+        self.stream().shuffle(items)
+    }
+
+    pub fn sample(&self, k: u64, n: u64) -> Result<Vec<u64>, Error> {
+        self.stream().sample(k, n)
+    }
+}
+
+// 2^256 mod n = (2^64 mod n)^4 mod n
+fn rejection_threshold(n: u64) -> u64 {
+    let n = n as u128;
+    let t = (u64::MAX as u128 % n + 1) % n;
+    let t = t * t % n;
+    (t * t % n) as u64
+}
+
+// threshold < n < 2^64, so only the lowest 8 bytes can be non-zero
+fn word_less_than(word: &[u8; WORD_LEN], threshold: u64) -> bool {
+    let (high, low) = word.split_at(WORD_LEN - 8);
+    high.iter().all(|b| *b == 0) && u64::from_be_bytes(low.try_into().unwrap()) < threshold
+}
+
+fn word_mod(word: &[u8; WORD_LEN], n: u64) -> u64 {
+    let n = n as u128;
+    let r = word.chunks(8).fold(0u128, |r, limb| {
+        let limb = u64::from_be_bytes(limb.try_into().unwrap()) as u128;
+        ((r << 64) | limb) % n
+    });
+    r as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2wrong::curves::bn256::G1Affine as BnG1;
+    use halo2wrong::curves::group::prime::PrimeCurveAffine;
+
+    // the vectors are shared with contracts/libs/RandomExpand.sol
+    #[test]
+    fn test_expand_vectors() {
+        let pseudo = PseudoRandom {
+            proof: BnG1::generator(),
+            value: (0..32).collect(),
+        };
+
+        assert_eq!(
+            hex::encode(pseudo.expand(80)),
+            "77355d4a862f94a7e5925b3054d446d88c8a9e46b64e69aa8a47fe70cffe75b8\
+             949509cbbed79fb3e62ecc1d288de81a017439fd44686577304bd00b4c1708d0\
+             c6cc0311ed7997384261dfeece1d316c"
+        );
+
+        let mut stream = pseudo.stream();
+        let draws: Vec<_> = [1, 2, 6, 100, u64::MAX]
+            .iter()
+            .map(|n| stream.uniform(*n).unwrap())
+            .collect();
+        assert_eq!(draws, vec![0, 0, 4, 30, 18318269273721899236]);
+        assert_eq!(stream.counter(), 5);
+
+        let mut items: Vec<_> = (0..10).collect();
+        pseudo.shuffle(&mut items);
+        assert_eq!(items, vec![7, 5, 6, 1, 3, 8, 0, 2, 9, 4]);
+
+        assert_eq!(pseudo.sample(3, 10).unwrap(), vec![4, 5, 0]);
+        assert_eq!(
+            pseudo.sample(5, 1000000).unwrap(),
+            vec![176824, 621635, 953632, 88455, 836624]
+        );
+
+        assert!(pseudo.uniform(0).is_err());
+        assert!(pseudo.sample(11, 10).is_err());
+    }
+
+    #[test]
+    fn test_uniform_helpers() {
+        assert_eq!(rejection_threshold(1), 0);
+        assert_eq!(rejection_threshold(3), 1);
+        assert_eq!(rejection_threshold(1 << 32), 0);
+        // 2^256 = 2^(4 * 64) = 1 mod (2^64 - 1)
+        assert_eq!(rejection_threshold(u64::MAX), 1);
+
+        let mut word = [0u8; WORD_LEN];
+        word[WORD_LEN - 1] = 1;
+        assert!(word_less_than(&word, 2));
+        assert!(!word_less_than(&word, 1));
+        word[0] = 1;
+        assert!(!word_less_than(&word, 2));
+
+        // 2^248 + 1 mod 3 = (-1)^248 + 1
+        assert_eq!(word_mod(&word, 3), 2);
+
+        // sampling all of 0..n gives a permutation
+        let mut stream = RandomStream::new(b"seed");
+        let mut sampled = stream.sample(20, 20).unwrap();
+        sampled.sort();
+        assert_eq!(sampled, (0..20).collect::<Vec<_>>());
+    }
+}
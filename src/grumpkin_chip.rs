@@ -218,6 +218,48 @@ impl GrumpkinChip {
         Ok(decomposed)
     }
 
+    // assign a non-zero value and decompose it into its canonical little endian bits,
+    // which compose back to the assigned value
+    pub fn to_bits_canonical(
+        &self,
+        ctx: &mut RegionCtx<'_, Base>,
+        value: &Value<Base>,
+    ) -> Result<(AssignedValue<Base>, Vec<AssignedCondition<Base>>), PlonkError> {
+        let main_gate = self.main_gate();
+        let assigned = main_gate.assign_value(ctx, *value)?;
+        main_gate.assert_not_zero(ctx, &assigned)?;
+
+        let bits = self.to_bits(ctx, &assigned)?;
+        self.assert_canonical(ctx, &bits)?;
+
+        Ok((assigned, bits))
+    }
+
+    // NUM_BITS bits can encode integers up to 2^NUM_BITS - 1 > p - 1, so both v and v + p
+    // compose to v modulo p; constrain the little endian bits to encode an integer at most p - 1
+    pub fn assert_canonical(
+        &self,
+        ctx: &mut RegionCtx<'_, Base>,
+        bits: &[AssignedCondition<Base>],
+    ) -> Result<(), PlonkError> {
+        let main_gate = self.main_gate();
+        let max: Vec<Base> = decompose(-Base::one(), bits.len(), 1);
+
+        // eq = 1 while the bits from the most significant one are equal to those of p - 1
+        let mut eq = main_gate.assign_constant(ctx, Base::one())?;
+        for (bit, m) in bits.iter().zip(max.iter()).rev() {
+            if *m == Base::one() {
+                eq = main_gate.mul(ctx, &eq, bit)?;
+            } else {
+                // a one where p - 1 has a zero would exceed p - 1
+                let exceeds = main_gate.mul(ctx, &eq, bit)?;
+                main_gate.assert_zero(ctx, &exceeds)?;
+            }
+        }
+
+        Ok(())
+    }
+
     // convert into bits without checking the bits compose back to the original value
     pub fn to_bits_unsafe(
         &self,
@@ -241,6 +283,58 @@ impl GrumpkinChip {
     }
 }
 
+// bits of the integer value + p, which compose to value modulo p
+#[cfg(test)]
+fn non_canonical_bits(value: Base) -> Vec<Base> {
+    let a = value.to_repr();
+    let b = (-Base::one()).to_repr();
+    let mut sum = [0u8; 32];
+    let mut carry = 1u16;
+    for i in 0..32 {
+        let s = a[i] as u16 + b[i] as u16 + carry;
+        sum[i] = s as u8;
+        carry = s >> 8;
+    }
+    assert_eq!(carry, 0);
+
+    (0..Base::NUM_BITS as usize)
+        .map(|i| Base::from(((sum[i / 8] >> (i % 8)) & 1) as u64))
+        .collect()
+}
+
+#[cfg(test)]
+impl GrumpkinChip {
+    // the same as to_bits_canonical with a malicious witness: the bits of value + p pass the
+    // composition check and are only rejected by assert_canonical
+    pub(crate) fn to_bits_non_canonical(
+        &self,
+        ctx: &mut RegionCtx<'_, Base>,
+        value: &Value<Base>,
+    ) -> Result<(AssignedValue<Base>, Vec<AssignedCondition<Base>>), PlonkError> {
+        let main_gate = self.main_gate();
+        let assigned = main_gate.assign_value(ctx, *value)?;
+        main_gate.assert_not_zero(ctx, &assigned)?;
+
+        let decomposed = value.map(non_canonical_bits);
+        let bits = (0..Base::NUM_BITS as usize)
+            .map(|i| main_gate.assign_bit(ctx, decomposed.as_ref().map(|bits| bits[i])))
+            .collect::<Result<Vec<_>, PlonkError>>()?;
+
+        let mut composed = main_gate.assign_constant(ctx, Base::zero())?;
+        let mut power = Base::one();
+        for bit in bits.iter() {
+            let coeff = main_gate.assign_constant(ctx, power)?;
+            composed = main_gate.mul_add(ctx, bit, &coeff, &composed)?;
+            power = power.double();
+        }
+        main_gate.assert_equal(ctx, &composed, &assigned)?;
+
+        self.assert_canonical(ctx, &bits)?;
+
+        Ok((assigned, bits))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,6 +345,7 @@ mod tests {
     use crate::hash_to_curve_grumpkin;
     use halo2wrong::halo2::arithmetic::Field;
     use halo2wrong::halo2::circuit::SimpleFloorPlanner;
+    use halo2wrong::halo2::dev::MockProver;
     use halo2wrong::halo2::plonk::{Circuit, ConstraintSystem};
     use halo2wrong::utils::{mock_prover_verify, DimensionMeasurement};
     use rand_chacha::ChaCha20Rng;
@@ -427,6 +522,79 @@ mod tests {
         }
     }
 
+    #[derive(Clone, Debug)]
+    struct TestBits {
+        value: Base,
+        // assign the bits of the integer value + p instead of the canonical bits
+        non_canonical: bool,
+    }
+
+    impl Circuit<Base> for TestBits {
+        type Config = MainGateConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            unimplemented!();
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Base>) -> Self::Config {
+            MainGate::<Base>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Base>,
+        ) -> Result<(), PlonkError> {
+            let ecc = GrumpkinChip::new(config);
+
+            layouter.assign_region(
+                || "region bits",
+                |region| {
+                    let offset = 0;
+                    let ctx = &mut RegionCtx::new(region, offset);
+
+                    if self.non_canonical {
+                        ecc.to_bits_non_canonical(ctx, &Value::known(self.value))?;
+                    } else {
+                        ecc.to_bits_canonical(ctx, &Value::known(self.value))?;
+                    }
+
+                    Ok(())
+                },
+            )?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_to_bits_canonical() {
+        let run = |value: Base, non_canonical: bool| {
+            let circuit = TestBits {
+                value,
+                non_canonical,
+            };
+            let dimension = DimensionMeasurement::measure(&circuit).unwrap();
+            MockProver::run(dimension.k(), &circuit, vec![vec![]])
+                .unwrap()
+                .verify()
+        };
+
+        let mut rng = OsRng;
+        assert!(run(Base::random(&mut rng), false).is_ok());
+        assert!(run(Base::from(5), false).is_ok());
+        // the largest canonical value
+        assert!(run(-Base::one(), false).is_ok());
+
+        // r = 0
+        assert!(run(Base::zero(), false).is_err());
+        // 5 + p composes to 5 but is not canonical
+        assert!(run(Base::from(5), true).is_err());
+    }
+
     #[test]
     fn test_grumpkin_mul() {
         let circuit = TestMul;
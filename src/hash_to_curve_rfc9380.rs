@@ -0,0 +1,244 @@
+// hash to Bn256 G1 with the hash_to_curve construction of RFC 9380
+// (https://www.rfc-editor.org/rfc/rfc9380.html), expand_message_xmd over SHA-256 and the
+// Shallue-van de Woestijne map. RFC 9380 defines no suite for BN254; the suite
+// BN254G1_XMD:SHA-256_SVDW_RO_ with L = 48, Z = 1 and a trivial cofactor is defined outside
+// the RFC and implemented e.g. by gnark-crypto (ecc/bn254). Unlike the keccak based hash of
+// contracts/libs/Hash.sol, it can be implemented by any verifier that follows the same suite.
+use crate::dkg::HashToCurve;
+use crate::hash_to_curve::{svdw_map_to_curve, svdw_precomputed_constants};
+use halo2wrong::curves::bn256::{Fq, G1Affine as BnG1, G1};
+use halo2wrong::curves::ff::{Field, FromUniformBytes};
+use halo2wrong::curves::group::Curve;
+use sha2::{Digest, Sha256};
+
+pub const RFC9380_SUITE: &str = "BN254G1_XMD:SHA-256_SVDW_RO_";
+
+// output and input block sizes of SHA-256
+const B_IN_BYTES: usize = 32;
+const S_IN_BYTES: usize = 64;
+// ceil((ceil(log2(p)) + k) / 8) with k = 128
+const L: usize = 48;
+
+// section 5.3.1; DST longer than 255 bytes is hashed as in section 5.3.3
+pub(crate) fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let ell = (len_in_bytes + B_IN_BYTES - 1) / B_IN_BYTES;
+    assert!(ell <= 255 && len_in_bytes <= u16::MAX as usize);
+
+    let dst = if dst.len() > 255 {
+        Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize()
+            .to_vec()
+    } else {
+        dst.to_vec()
+    };
+    let mut dst_prime = dst;
+    dst_prime.push(dst_prime.len() as u8);
+
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let xored: Vec<_> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = Sha256::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend(b_i);
+    }
+
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+// section 5.2 with count = 2 and m = 1
+fn hash_to_field(msg: &[u8], dst: &[u8]) -> [Fq; 2] {
+    let uniform_bytes = expand_message_xmd(msg, dst, 2 * L);
+
+    let mut us = [Fq::ZERO; 2];
+    for (u, tv) in us.iter_mut().zip(uniform_bytes.chunks(L)) {
+        // OS2IP(tv) mod p, from_uniform_bytes reads 64 bytes in little endian
+        let mut bytes = [0u8; 64];
+        bytes[..L].copy_from_slice(tv);
+        bytes[..L].reverse();
+        *u = Fq::from_uniform_bytes(&bytes);
+    }
+
+    us
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rfc9380Domain {
+    dst: Vec<u8>,
+}
+
+impl Rfc9380Domain {
+    // the RFC recommends tags of the form "<application>-V<xx>-CS<yy>-with-BN254G1_XMD:SHA-256_SVDW_RO_"
+    pub fn new(dst: &[u8]) -> Self {
+        Rfc9380Domain { dst: dst.to_vec() }
+    }
+
+    pub fn dst(&self) -> &[u8] {
+        &self.dst
+    }
+}
+
+impl HashToCurve for Rfc9380Domain {
+    fn hash_to_curve(&self, input: &[u8]) -> BnG1 {
+        let z = Fq::ONE;
+        let [c1, c2, c3, c4] = svdw_precomputed_constants::<G1>(z);
+
+        let [q0, q1] =
+            hash_to_field(input, &self.dst).map(|u| svdw_map_to_curve::<G1>(u, c1, c2, c3, c4, z));
+
+        // the cofactor of Bn256 G1 is 1
+        (q0 + q1).to_affine()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::{DkgConfig, DkgShareKey, EvalDomain, PseudoRandom};
+    use crate::{combine_partial_evaluations, shares};
+    use halo2wrong::curves::bn256::{Fr as BnScalar, G2Affine as BnG2};
+    use halo2wrong::curves::ff::PrimeField;
+    use halo2wrong::curves::group::prime::PrimeCurveAffine;
+    use rand_core::OsRng;
+
+    fn fq_from_hex(s: &str) -> Fq {
+        let mut bytes: [u8; 32] = hex::decode(format!("{s:0>64}"))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        bytes.reverse();
+        Fq::from_repr(bytes).unwrap()
+    }
+
+    // RFC 9380 appendix K.1
+    #[test]
+    fn test_expand_message_xmd() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let vectors: [(&[u8], usize, &str); 4] = [
+            (
+                b"",
+                0x20,
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                b"abc",
+                0x20,
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+            (
+                b"",
+                0x80,
+                "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbe\
+                 e0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18\
+                 eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc\
+                 c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced",
+            ),
+            (
+                b"abcdef0123456789",
+                0x80,
+                "ef904a29bffc4cf9ee82832451c946ac3c8f8058ae97d8d629831a74c6572bd9\
+                 ebd0df635cd1f208e2038e760c4994984ce73f0d55ea9f22af83ba4734569d4b\
+                 c95e18350f740c07eef653cbb9f87910d833751825f0ebefa1abe5420bb52be1\
+                 4cf489b37fe1a72f7de2d10be453b2c9d9eb20c7e3f6edc5a60629178d9478df",
+            ),
+        ];
+
+        for (msg, len, expected) in vectors {
+            assert_eq!(hex::encode(expand_message_xmd(msg, dst, len)), expected);
+        }
+    }
+
+    // the hashToG1Vector test vectors of gnark-crypto ecc/bn254/hash_vectors_test.go for
+    // BN254G1_XMD:SHA-256_SVDW_RO_; RFC 9380 has no vectors for BN254
+    #[test]
+    fn test_hash_to_curve_rfc9380() {
+        let domain = Rfc9380Domain::new(b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_");
+        let vectors: [(&[u8], [&str; 2], [&str; 2]); 2] = [
+            (
+                b"",
+                [
+                    "2f87b81d9d6ef05ad4d249737498cc27e1bd485dca804487844feb3c67c1a9b5",
+                    "6de2d0d7c0d9c7a5a6c0b74675e7543f5b98186b5dbf831067449000b2b1f8e",
+                ],
+                [
+                    "a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86",
+                    "2925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5",
+                ],
+            ),
+            (
+                b"abc",
+                [
+                    "11945105b5e3d3b9392b5a2318409cbc28b7246aa47fa30da5739907737799a9",
+                    "1255fc9ad5a6e0fb440916f091229bda611c41be2f2283c3d8f98c596be4c8c9",
+                ],
+                [
+                    "23f717bee89b1003957139f193e6be7da1df5f1374b26a4643b0378b5baf53d1",
+                    "4142f826b71ee574452dbc47e05bc3e1a647478403a7ba38b7b93948f4e151d",
+                ],
+            ),
+        ];
+
+        for (msg, u, p) in vectors {
+            assert_eq!(
+                hash_to_field(msg, domain.dst()),
+                [fq_from_hex(u[0]), fq_from_hex(u[1])]
+            );
+            let h = domain.hash_to_curve(msg);
+            assert_eq!(h.x, fq_from_hex(p[0]));
+            assert_eq!(h.y, fq_from_hex(p[1]));
+        }
+    }
+
+    #[test]
+    fn test_evaluate_rfc9380() {
+        let mut rng = OsRng;
+        let dkg_config = DkgConfig::new(2, 3).unwrap();
+        let coeffs = [BnScalar::from(7), BnScalar::from(11)];
+        let share_keys: Vec<_> = shares(3, &coeffs)
+            .into_iter()
+            .enumerate()
+            .map(|(i, sk)| DkgShareKey::new(i + 1, sk, (BnG1::generator() * sk).to_affine()))
+            .collect();
+        let gpk = (BnG2::generator() * coeffs[0]).to_affine();
+
+        let domain = Rfc9380Domain::new(b"ZKRAND-V01-CS01-with-BN254G1_XMD:SHA-256_SVDW_RO_");
+        let input = b"non-evm input";
+        let sigmas: Vec<_> = share_keys[0..2]
+            .iter()
+            .map(|s| s.evaluate(&domain, input, &mut rng))
+            .collect();
+        for sigma in sigmas.iter() {
+            sigma
+                .verify(
+                    &dkg_config,
+                    &domain,
+                    input,
+                    &share_keys[sigma.index - 1].verify_key(),
+                )
+                .unwrap();
+        }
+
+        let pseudo: PseudoRandom = combine_partial_evaluations(&dkg_config, &sigmas).unwrap();
+        pseudo.verify(&domain, input, &gpk).unwrap();
+        assert!(pseudo.verify(&EvalDomain::default(), input, &gpk).is_err());
+    }
+}
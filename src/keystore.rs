@@ -0,0 +1,391 @@
+// password encrypted storage for member keys and share keys, similar to the Ethereum v3 keystore;
+// 64 bytes are derived from the password with scrypt or pbkdf2, the first half is the AES-256-GCM
+// key for the secret key and the second half authenticates the ciphertext with a keccak MAC,
+// so that a wrong password is reported as such rather than as a decryption failure
+use crate::dkg::DkgShareKey;
+use crate::error::Error;
+use crate::secret::Secret;
+use crate::serialise::Point;
+use crate::MemberKey;
+use aes_gcm::aead::{Aead, AeadInPlace, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce, Tag};
+use halo2wrong::curves::bn256::{Fr as BnScalar, G1Affine as BnG1};
+use halo2wrong::curves::ff::PrimeField;
+use halo2wrong::curves::group::Curve;
+use halo2wrong::curves::grumpkin::{Fr as GkScalar, G1Affine as GkG1};
+use halo2wrong::curves::CurveAffine;
+use hex::{decode, encode};
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+use subtle::ConstantTimeEq;
+
+pub const KEYSTORE_VERSION: u32 = 1;
+const CIPHER: &str = "aes-256-gcm";
+const PRF: &str = "hmac-sha256";
+const DKLEN: usize = 64;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    Scrypt { log_n: u8, r: u32, p: u32 },
+    Pbkdf2 { c: u32 },
+}
+
+impl Kdf {
+    // the parameters of the Ethereum keystore
+    pub fn scrypt() -> Self {
+        Kdf::Scrypt {
+            log_n: 18,
+            r: 8,
+            p: 1,
+        }
+    }
+
+    pub fn pbkdf2() -> Self {
+        Kdf::Pbkdf2 { c: 262144 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KdfParams {
+    Scrypt {
+        dklen: usize,
+        n: u32,
+        r: u32,
+        p: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        dklen: usize,
+        c: u32,
+        prf: String,
+        salt: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CipherParams {
+    pub nonce: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    pub cipher: String,
+    pub cipherparams: CipherParams,
+    pub ciphertext: String,
+    pub kdf: String,
+    pub kdfparams: KdfParams,
+    pub mac: String,
+}
+
+// the public part of the encrypted key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum KeystoreKey {
+    Member { pk: Point },
+    Share { index: usize, epoch: u64, vk: Point },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub key: KeystoreKey,
+    pub crypto: KeystoreCrypto,
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidKeystore(reason.to_string())
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, Error> {
+    decode(s).map_err(|_| invalid("invalid hex string"))
+}
+
+fn derive_key(password: &[u8], params: &KdfParams) -> Result<Secret<[u8; DKLEN]>, Error> {
+    let mut dk = Secret::new([0u8; DKLEN]);
+    match params {
+        KdfParams::Scrypt {
+            dklen,
+            n,
+            r,
+            p,
+            salt,
+        } => {
+            if *dklen != DKLEN || !n.is_power_of_two() || *n < 2 {
+                return Err(invalid("invalid scrypt parameters"));
+            }
+            let log_n = n.trailing_zeros() as u8;
+            let params = scrypt::Params::new(log_n, *r, *p, DKLEN)
+                .map_err(|_| invalid("invalid scrypt parameters"))?;
+            scrypt::scrypt(
+                password,
+                &decode_hex(salt)?,
+                &params,
+                dk.expose_secret_mut(),
+            )
+            .map_err(|_| invalid("invalid scrypt parameters"))?;
+        }
+        KdfParams::Pbkdf2 {
+            dklen,
+            c,
+            prf,
+            salt,
+        } => {
+            if *dklen != DKLEN || prf != PRF || *c == 0 {
+                return Err(invalid("invalid pbkdf2 parameters"));
+            }
+            pbkdf2::pbkdf2_hmac::<Sha256>(password, &decode_hex(salt)?, *c, dk.expose_secret_mut());
+        }
+    }
+
+    Ok(dk)
+}
+
+fn mac(dk: &[u8; DKLEN], ciphertext: &[u8]) -> [u8; 32] {
+    Keccak256::new()
+        .chain_update(&dk[32..])
+        .chain_update(ciphertext)
+        .finalize()
+        .to_vec()
+        .try_into()
+        .unwrap()
+}
+
+fn encrypt(
+    secret: &Secret<[u8; 32]>,
+    password: &[u8],
+    kdf: Kdf,
+    mut rng: impl RngCore,
+) -> Result<KeystoreCrypto, Error> {
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+
+    let (kdf, kdfparams) = match kdf {
+        Kdf::Scrypt { log_n, r, p } => (
+            "scrypt",
+            KdfParams::Scrypt {
+                dklen: DKLEN,
+                n: 1u32
+                    .checked_shl(log_n as u32)
+                    .ok_or_else(|| invalid("invalid scrypt parameters"))?,
+                r,
+                p,
+                salt: encode(salt),
+            },
+        ),
+        Kdf::Pbkdf2 { c } => (
+            "pbkdf2",
+            KdfParams::Pbkdf2 {
+                dklen: DKLEN,
+                c,
+                prf: PRF.to_string(),
+                salt: encode(salt),
+            },
+        ),
+    };
+
+    let dk = derive_key(password, &kdfparams)?;
+    let cipher = Aes256Gcm::new_from_slice(&dk.expose_secret()[..32]).unwrap();
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), secret.expose_secret().as_ref())
+        .map_err(|_| invalid("encryption failed"))?;
+    let mac = mac(dk.expose_secret(), &ciphertext);
+
+    Ok(KeystoreCrypto {
+        cipher: CIPHER.to_string(),
+        cipherparams: CipherParams {
+            nonce: encode(nonce),
+        },
+        ciphertext: encode(ciphertext),
+        kdf: kdf.to_string(),
+        kdfparams,
+        mac: encode(mac),
+    })
+}
+
+fn decrypt(crypto: &KeystoreCrypto, password: &[u8]) -> Result<Secret<[u8; 32]>, Error> {
+    if crypto.cipher != CIPHER {
+        return Err(invalid("unsupported cipher"));
+    }
+    match (crypto.kdf.as_str(), &crypto.kdfparams) {
+        ("scrypt", KdfParams::Scrypt { .. }) | ("pbkdf2", KdfParams::Pbkdf2 { .. }) => {}
+        _ => return Err(invalid("unsupported kdf")),
+    }
+
+    let dk = derive_key(password, &crypto.kdfparams)?;
+    let ciphertext = decode_hex(&crypto.ciphertext)?;
+    let expected = decode_hex(&crypto.mac)?;
+    if !bool::from(mac(dk.expose_secret(), &ciphertext)[..].ct_eq(&expected)) {
+        return Err(Error::InvalidPassword);
+    }
+
+    let nonce = decode_hex(&crypto.cipherparams.nonce)?;
+    if nonce.len() != NONCE_LEN {
+        return Err(invalid("invalid nonce"));
+    }
+    if ciphertext.len() != 32 + TAG_LEN {
+        return Err(invalid("invalid secret key length"));
+    }
+
+    // decrypt in place so that the secret key is only ever held in the wiped buffer
+    let (body, tag) = ciphertext.split_at(32);
+    let mut secret = Secret::new([0u8; 32]);
+    secret.expose_secret_mut().copy_from_slice(body);
+    let cipher = Aes256Gcm::new_from_slice(&dk.expose_secret()[..32]).unwrap();
+    cipher
+        .decrypt_in_place_detached(
+            Nonce::from_slice(&nonce),
+            b"",
+            secret.expose_secret_mut(),
+            Tag::from_slice(tag),
+        )
+        .map_err(|_| invalid("decryption failed"))?;
+    Ok(secret)
+}
+
+impl MemberKey {
+    pub fn encrypt(&self, password: &[u8], kdf: Kdf, rng: impl RngCore) -> Result<Keystore, Error> {
+        let secret = Secret::new(self.secret_key().to_repr());
+        Ok(Keystore {
+            version: KEYSTORE_VERSION,
+            key: KeystoreKey::Member {
+                pk: self.public_key().into(),
+            },
+            crypto: encrypt(&secret, password, kdf, rng)?,
+        })
+    }
+}
+
+impl DkgShareKey {
+    pub fn encrypt(&self, password: &[u8], kdf: Kdf, rng: impl RngCore) -> Result<Keystore, Error> {
+        let secret = Secret::new(self.secret_key().to_repr());
+        Ok(Keystore {
+            version: KEYSTORE_VERSION,
+            key: KeystoreKey::Share {
+                index: self.index(),
+                epoch: self.epoch(),
+                vk: self.verify_key().into(),
+            },
+            crypto: encrypt(&secret, password, kdf, rng)?,
+        })
+    }
+}
+
+impl Keystore {
+    fn check_version(&self) -> Result<(), Error> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(invalid("unsupported keystore version"));
+        }
+        Ok(())
+    }
+
+    pub fn decrypt_member_key(&self, password: &[u8]) -> Result<MemberKey, Error> {
+        self.check_version()?;
+        let pk = match &self.key {
+            KeystoreKey::Member { pk } => GkG1::try_from(pk)?,
+            _ => return Err(invalid("not a member key")),
+        };
+
+        let secret = decrypt(&self.crypto, password)?;
+        let sk: GkScalar = Option::from(GkScalar::from_repr(*secret.expose_secret()))
+            .ok_or_else(|| invalid("invalid secret key"))?;
+        if (GkG1::generator() * sk).to_affine() != pk {
+            return Err(invalid("public key does not match secret key"));
+        }
+
+        Ok(MemberKey::new(sk, pk))
+    }
+
+    pub fn decrypt_share_key(&self, password: &[u8]) -> Result<DkgShareKey, Error> {
+        self.check_version()?;
+        let (index, epoch, vk) = match &self.key {
+            KeystoreKey::Share { index, epoch, vk } => (*index, *epoch, BnG1::try_from(vk)?),
+            _ => return Err(invalid("not a share key")),
+        };
+
+        let secret = decrypt(&self.crypto, password)?;
+        let sk: BnScalar = Option::from(BnScalar::from_repr(*secret.expose_secret()))
+            .ok_or_else(|| invalid("invalid secret key"))?;
+        if (BnG1::generator() * sk).to_affine() != vk {
+            return Err(invalid("verification key does not match secret key"));
+        }
+
+        Ok(DkgShareKey::new_with_epoch(index, sk, vk, epoch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::keygen;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_keystore() {
+        let mut rng = OsRng;
+        let password = b"correct horse battery staple";
+        // light parameters to keep the test fast
+        let kdfs = [
+            Kdf::Scrypt {
+                log_n: 10,
+                r: 8,
+                p: 1,
+            },
+            Kdf::Pbkdf2 { c: 1000 },
+        ];
+
+        for kdf in kdfs {
+            let member = MemberKey::random(&mut rng);
+            let keystore = member.encrypt(password, kdf, &mut rng).unwrap();
+            let json = serde_json::to_string(&keystore).unwrap();
+            assert!(!json.contains(&encode(member.secret_key().to_repr())));
+
+            let keystore: Keystore = serde_json::from_str(&json).unwrap();
+            let decrypted = keystore.decrypt_member_key(password).unwrap();
+            assert_eq!(decrypted.secret_key(), member.secret_key());
+            assert!(matches!(
+                keystore.decrypt_member_key(b"wrong password"),
+                Err(Error::InvalidPassword)
+            ));
+            assert!(keystore.decrypt_share_key(password).is_err());
+
+            let (sk, vk) = keygen(&mut rng);
+            let share = DkgShareKey::new_with_epoch(3, sk, vk, 2);
+            let keystore = share.encrypt(password, kdf, &mut rng).unwrap();
+            let decrypted = keystore.decrypt_share_key(password).unwrap();
+            assert_eq!(decrypted.secret_key(), &sk);
+            assert_eq!(decrypted.index(), 3);
+            assert_eq!(decrypted.epoch(), 2);
+
+            // tampered ciphertext fails the mac
+            let mut tampered = keystore.clone();
+            let mut ciphertext = decode(&tampered.crypto.ciphertext).unwrap();
+            ciphertext[0] ^= 1;
+            tampered.crypto.ciphertext = encode(ciphertext);
+            assert!(tampered.decrypt_share_key(password).is_err());
+
+            // a ciphertext of the wrong length is rejected before decryption
+            let mut truncated = keystore.clone();
+            let mut ciphertext = decode(&truncated.crypto.ciphertext).unwrap();
+            ciphertext.pop();
+            truncated.crypto.mac = encode(mac(
+                derive_key(password, &truncated.crypto.kdfparams)
+                    .unwrap()
+                    .expose_secret(),
+                &ciphertext,
+            ));
+            truncated.crypto.ciphertext = encode(ciphertext);
+            assert!(matches!(
+                truncated.decrypt_share_key(password),
+                Err(Error::InvalidKeystore(_))
+            ));
+        }
+    }
+}
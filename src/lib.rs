@@ -1,16 +1,36 @@
+mod complaint;
+mod compressed;
 pub mod dkg;
 pub mod dkg_circuit;
 #[allow(dead_code)]
 mod ecc_chip;
 mod error;
+mod expand;
 #[allow(dead_code)]
 mod grumpkin_chip;
 mod hash_to_curve;
 mod hash_to_curve_evm;
+mod hash_to_curve_rfc9380;
+#[cfg(feature = "keystore")]
+pub mod keystore;
+mod plan;
 mod poseidon;
+mod proof;
+mod qualified;
+mod refresh;
+mod reshare;
+mod secret;
+#[cfg(feature = "serde")]
+pub mod serialise;
+mod sign;
 mod utils;
+mod weighted;
+pub mod wire;
 
-pub use utils::{load_or_create_params, load_or_create_pk, load_or_create_vk};
+pub use utils::{
+    load_or_create_params, load_or_create_pk, load_or_create_pk_of, load_or_create_vk,
+    load_or_create_vk_of,
+};
 
 use rand_core::RngCore;
 use std::rc::Rc;
@@ -18,7 +38,6 @@ use std::rc::Rc;
 pub use halo2_ecc::integer::NUMBER_OF_LOOKUP_LIMBS;
 use halo2_gadgets::poseidon::primitives::{ConstantLength, Hash};
 use halo2wrong::curves::bn256::{Fr as BnScalar, G1Affine as BnG1, G2Affine as BnG2};
-use halo2wrong::curves::ff::PrimeField;
 use halo2wrong::curves::group::prime::PrimeCurveAffine;
 use halo2wrong::curves::group::Curve;
 use halo2wrong::curves::grumpkin::{Fr as GkScalar, G1Affine as GkG1};
@@ -26,17 +45,40 @@ use halo2wrong::curves::CurveAffine;
 use halo2wrong::halo2::arithmetic::Field;
 use halo2wrong::halo2::circuit::Value;
 
+pub use crate::complaint::{Complaint, DleqProof};
+pub use crate::compressed::commit_public_data;
 pub use crate::dkg::{
-    combine_partial_evaluations, is_dl_equal, keygen, shares, DkgConfig, DkgShareKey, PseudoRandom,
-    EVAL_PREFIX,
+    check_public_shares, check_verify_keys, combine_partial_evaluations,
+    combine_partial_evaluations_robust, is_dl_equal, keygen, shares, verify_batchable_evaluations,
+    verify_partial_evaluations, BatchablePartialEval, CombinedEvaluations, DkgConfig, DkgShareKey,
+    EvalDomain, HashToCurve, PseudoRandom, RejectedEvaluation, RejectionReason, EVAL_PREFIX,
 };
-pub use crate::dkg_circuit::DkgCircuit;
+pub use crate::dkg_circuit::{CircuitKind, DkgCircuit};
 pub use crate::error::Error;
+pub use crate::expand::RandomStream;
+pub use crate::hash_to_curve_rfc9380::{Rfc9380Domain, RFC9380_SUITE};
+pub use crate::plan::{plan, CircuitPlan};
 pub use crate::poseidon::P128Pow5T3Bn;
+pub use crate::proof::{create_proof_checked, verify_batch, verify_single};
+pub use crate::qualified::{
+    qualified_dealers, DkgDerivation, ExcludedDealer, ExclusionReason, QualifiedPolicy,
+    QualifiedSet,
+};
+pub use crate::refresh::refresh_global_public_params;
+pub use crate::reshare::{reshare_global_public_params, ReshareDealing};
+pub use crate::secret::Secret;
+pub use crate::sign::{
+    aggregate_signature_shares, aggregate_signatures, verify_aggregate_signature,
+    verify_signature_shares, Signature, SignatureShare, SIGN_PREFIX,
+};
+use crate::utils::{
+    bn_to_grumpkin_scalar, check_length, is_in_g2_subgroup, point_to_public, public_to_grumpkin,
+    public_to_point,
+};
 pub use crate::utils::{hash_to_curve_bn, hash_to_curve_grumpkin, mod_n, rns_setup};
 #[cfg(feature = "g2chip")]
 use crate::utils::{point2_to_public, public_to_point2};
-use crate::utils::{point_to_public, public_to_point};
+pub use crate::weighted::{combine_weighted_partial_evaluations, WeightedConfig};
 
 const BIT_LEN_LIMB: usize = 68;
 const NUMBER_OF_LIMBS: usize = 4;
@@ -50,15 +92,24 @@ const POSEIDON_RATE: usize = 2;
 const POSEIDON_LEN: usize = 2;
 pub const WINDOW_SIZE: usize = 3;
 
+// derive the symmetric key for encrypting a share from pk^r
+pub(crate) fn encryption_key(pkr: &GkG1) -> BnScalar {
+    let poseidon = Hash::<_, P128Pow5T3Bn, ConstantLength<2>, 3, 2>::init();
+    poseidon.hash([pkr.x, pkr.y])
+}
+
 #[derive(Debug, Clone)]
 pub struct MemberKey {
-    sk: GkScalar,
+    sk: Secret<GkScalar>,
     pk: GkG1,
 }
 
 impl MemberKey {
     pub fn new(sk: GkScalar, pk: GkG1) -> Self {
-        MemberKey { sk, pk }
+        MemberKey {
+            sk: Secret::new(sk),
+            pk,
+        }
     }
 
     pub fn random(mut rng: impl RngCore) -> Self {
@@ -66,21 +117,20 @@ impl MemberKey {
         let sk = GkScalar::random(&mut rng);
         let pk = (g * sk).to_affine();
 
-        MemberKey { sk, pk }
+        MemberKey::new(sk, pk)
     }
 
     pub fn public_key(&self) -> GkG1 {
         self.pk
     }
 
-    pub fn secret_key(&self) -> GkScalar {
-        self.sk
+    pub fn secret_key(&self) -> &GkScalar {
+        self.sk.expose_secret()
     }
 
     pub fn decrypt_share(&self, gr: &GkG1, cipher: &BnScalar) -> BnScalar {
-        let pkr = (gr * self.sk).to_affine();
-        let poseidon = Hash::<_, P128Pow5T3Bn, ConstantLength<2>, 3, 2>::init();
-        let key = poseidon.clone().hash([pkr.x, pkr.y]);
+        let pkr = (gr * self.sk.expose_secret()).to_affine();
+        let key = encryption_key(&pkr);
         let plaintext = cipher - key;
 
         plaintext
@@ -105,6 +155,10 @@ impl MemberKey {
             return Err(Error::InvalidIndex { index });
         }
 
+        for &pp in pps.iter() {
+            pp.check_lengths(dkg_config)?;
+        }
+
         let k = index - 1;
         let mut sk = BnScalar::zero();
         for &pp in pps.iter() {
@@ -167,29 +221,85 @@ impl DkgMemberPublicParams {
         is_dl_equal(&self.ga, &self.g2a)
     }
 
+    // check if the public shares and ga are on a polynomial of degree less than threshold,
+    // which is much cheaper than verifying the dkg proof
+    pub fn check_low_degree(&self, dkg_config: &DkgConfig, rng: impl RngCore) -> Result<(), Error> {
+        check_public_shares(dkg_config, &self.ga, &self.public_shares, rng)
+    }
+
+    // check if there is a public share and a cipher for every member
+    pub fn check_lengths(&self, dkg_config: &DkgConfig) -> Result<(), Error> {
+        let n = dkg_config.number_of_members();
+        check_length(n, self.public_shares.len())?;
+        check_length(n, self.ciphers.len())?;
+
+        Ok(())
+    }
+
     #[cfg(feature = "g2chip")]
-    pub fn from_instance(dkg_config: &DkgConfig, instance: &[BnScalar]) -> (Self, Vec<GkG1>) {
+    pub fn from_instance(
+        dkg_config: &DkgConfig,
+        instance: &[BnScalar],
+    ) -> Result<(Self, Vec<GkG1>), Error> {
+        Self::decode_instance(dkg_config, instance)
+    }
+
+    // without g2chip the circuit does not expose g2a, so the dealer publishes it alongside
+    // the instance and it is accepted only if it has the same exponent as the proven ga
+    #[cfg(not(feature = "g2chip"))]
+    pub fn from_instance_with_g2a(
+        dkg_config: &DkgConfig,
+        instance: &[BnScalar],
+        g2a: &BnG2,
+    ) -> Result<(Self, Vec<GkG1>), Error> {
+        if !is_in_g2_subgroup(g2a) {
+            return Err(Error::InvalidPoint);
+        }
+
+        let (pp, pks) = Self::decode_instance(dkg_config, instance, *g2a)?;
+        pp.check_public()?;
+
+        Ok((pp, pks))
+    }
+
+    fn decode_instance(
+        dkg_config: &DkgConfig,
+        instance: &[BnScalar],
+        #[cfg(not(feature = "g2chip"))] g2a: BnG2,
+    ) -> Result<(Self, Vec<GkG1>), Error> {
         let len = dkg_config.instance_size();
-        assert_eq!(len, instance.len());
+        if len != instance.len() {
+            return Err(Error::MalformedInstance(format!(
+                "expected {len} elements, got {}",
+                instance.len()
+            )));
+        }
 
         // read ga
-        let ga: BnG1 = public_to_point(&instance[0..POINT_LEN]);
+        let ga: BnG1 = public_to_point(&instance[0..POINT_LEN])?;
 
         // read gs1,..., gs_n
         let mut public_shares: Vec<BnG1> = vec![];
         let mut begin = POINT_LEN;
         for _ in 0..dkg_config.number_of_members() {
-            let gs: BnG1 = public_to_point(&instance[begin..begin + POINT_LEN]);
+            let gs: BnG1 = public_to_point(&instance[begin..begin + POINT_LEN])?;
             public_shares.push(gs);
             begin += POINT_LEN;
         }
 
         // read g2a
-        let g2a: BnG2 = public_to_point2(&instance[begin..begin + POINT2_LEN]);
-        begin += POINT2_LEN;
+        #[cfg(feature = "g2chip")]
+        let g2a = {
+            let g2a: BnG2 = public_to_point2(&instance[begin..begin + POINT2_LEN])?;
+            if !is_in_g2_subgroup(&g2a) {
+                return Err(Error::InvalidPoint);
+            }
+            begin += POINT2_LEN;
+            g2a
+        };
 
         // read gr
-        let gr = GkG1::from_xy(instance[begin], instance[begin + 1]).unwrap();
+        let gr = public_to_grumpkin(instance[begin], instance[begin + 1])?;
         begin += 2;
 
         // read cipher_1, ..., cipher_n
@@ -204,7 +314,7 @@ impl DkgMemberPublicParams {
         // read pk_1, ..., pk_n
         let mut pks = vec![];
         for _ in 0..dkg_config.number_of_members() {
-            let pk = GkG1::from_xy(instance[begin], instance[begin + 1]).unwrap();
+            let pk = public_to_grumpkin(instance[begin], instance[begin + 1])?;
             pks.push(pk);
 
             begin += 2;
@@ -218,16 +328,16 @@ impl DkgMemberPublicParams {
             g2a,
         };
 
-        (pp, pks)
+        Ok((pp, pks))
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct DkgMemberParams {
     pub dkg_config: DkgConfig,
-    pub coeffs: Vec<BnScalar>,
-    pub shares: Vec<BnScalar>,
-    pub r: BnScalar,
+    pub coeffs: Secret<Vec<BnScalar>>,
+    pub shares: Secret<Vec<BnScalar>>,
+    pub r: Secret<BnScalar>,
     pub public_keys: Vec<GkG1>,
     pub public_params: DkgMemberPublicParams,
 }
@@ -238,13 +348,23 @@ impl DkgMemberParams {
         public_keys: Vec<GkG1>,
         mut rng: impl RngCore,
     ) -> Result<Self, Error> {
-        assert_eq!(public_keys.len(), dkg_config.number_of_members());
+        check_length(dkg_config.number_of_members(), public_keys.len())?;
 
         // generate random coefficients for polynomial
         let coeffs: Vec<_> = (0..dkg_config.threshold())
             .map(|_| BnScalar::random(&mut rng))
             .collect();
 
+        Ok(Self::deal(dkg_config, coeffs, public_keys, rng))
+    }
+
+    // compute and encrypt the shares of the polynomial defined by coeffs
+    pub(crate) fn deal(
+        dkg_config: DkgConfig,
+        coeffs: Vec<BnScalar>,
+        public_keys: Vec<GkG1>,
+        mut rng: impl RngCore,
+    ) -> Self {
         let g = BnG1::generator();
         let g2 = BnG2::generator();
 
@@ -256,19 +376,22 @@ impl DkgMemberParams {
         let shares = shares(dkg_config.number_of_members(), &coeffs);
         let public_shares: Vec<_> = shares.iter().map(|s| (g * s).to_affine()).collect();
 
-        // draw arandomness for encryption
-        let r = BnScalar::random(&mut rng);
+        // draw non-zero randomness for encryption; the circuit rejects r = 0
+        let r = Secret::new(loop {
+            let r = BnScalar::random(&mut rng);
+            if !bool::from(r.is_zero()) {
+                break r;
+            }
+        });
         let gg = GkG1::generator();
-        let rs = GkScalar::from_repr(r.to_repr())
-            .expect("unable to convert Bn256 scalar to Grumpkin scalar");
-        let gr = (gg * rs).to_affine();
+        let rs = Secret::new(bn_to_grumpkin_scalar(r.expose_secret()));
+        let gr = (gg * rs.expose_secret()).to_affine();
 
         // encrypt shares
-        let poseidon = Hash::<_, P128Pow5T3Bn, ConstantLength<2>, 3, 2>::init();
         let mut ciphers = vec![];
         for i in 0..dkg_config.number_of_members() {
-            let pkr = (public_keys[i] * rs).to_affine();
-            let key = poseidon.clone().hash([pkr.x, pkr.y]);
+            let pkr = (public_keys[i] * rs.expose_secret()).to_affine();
+            let key = encryption_key(&pkr);
             let cipher = key + shares[i];
             ciphers.push(cipher);
         }
@@ -281,18 +404,23 @@ impl DkgMemberParams {
             g2a,
         };
 
-        Ok(DkgMemberParams {
+        DkgMemberParams {
             dkg_config,
-            coeffs,
-            shares,
+            coeffs: Secret::new(coeffs),
+            shares: Secret::new(shares),
             r,
             public_keys,
             public_params,
-        })
+        }
     }
 
     pub fn circuit(&self, mut rng: impl RngCore) -> DkgCircuit {
-        let coeffs: Vec<_> = self.coeffs.iter().map(|a| Value::known(*a)).collect();
+        let coeffs: Vec<_> = self
+            .coeffs
+            .expose_secret()
+            .iter()
+            .map(|a| Value::known(*a))
+            .collect();
         let public_keys: Vec<_> = self
             .public_keys
             .iter()
@@ -303,7 +431,7 @@ impl DkgMemberParams {
         let circuit = DkgCircuit::new(
             self.dkg_config,
             coeffs,
-            Value::known(self.r),
+            Value::known(*self.r.expose_secret()),
             public_keys,
             grumpkin_aux_generator,
         );
@@ -332,9 +460,27 @@ impl DkgGlobalPubParams {
     pub fn check_public(&self) -> Result<(), Error> {
         is_dl_equal(&self.ga, &self.g2a)
     }
+
+    // check if the verification keys are shares of ga
+    pub fn check_low_degree(&self, dkg_config: &DkgConfig, rng: impl RngCore) -> Result<(), Error> {
+        check_public_shares(dkg_config, &self.ga, &self.verify_keys, rng)
+    }
 }
 
-pub fn dkg_global_public_params(pps: &[&DkgMemberPublicParams]) -> DkgGlobalPubParams {
+pub fn dkg_global_public_params(
+    dkg_config: &DkgConfig,
+    pps: &[&DkgMemberPublicParams],
+) -> Result<DkgGlobalPubParams, Error> {
+    if pps.is_empty() {
+        return Err(Error::NotEnoughDealers {
+            qualified: 0,
+            required: 1,
+        });
+    }
+    for &pp in pps.iter() {
+        pp.check_lengths(dkg_config)?;
+    }
+
     // combine ga and g2a to get global public keys
     let ga = pps
         .iter()
@@ -347,8 +493,7 @@ pub fn dkg_global_public_params(pps: &[&DkgMemberPublicParams]) -> DkgGlobalPubP
 
     // compute vk_1, ... vk_n
     let mut vks = vec![];
-    let number_of_members = pps[0].public_shares.len();
-    for i in 0..number_of_members {
+    for i in 0..dkg_config.number_of_members() {
         let mut vk = pps[0].public_shares[i].to_curve();
         for pp in pps.iter().skip(1) {
             vk = vk + pp.public_shares[i];
@@ -356,11 +501,11 @@ pub fn dkg_global_public_params(pps: &[&DkgMemberPublicParams]) -> DkgGlobalPubP
         vks.push(vk.to_affine());
     }
 
-    DkgGlobalPubParams {
+    Ok(DkgGlobalPubParams {
         ga,
         g2a,
         verify_keys: vks,
-    }
+    })
 }
 
 #[cfg(test)]
@@ -371,6 +516,7 @@ mod tests {
     use ark_std::{end_timer, start_timer};
     use halo2_ecc::halo2::SerdeFormat;
     use halo2wrong::curves::bn256::Bn256;
+    use halo2wrong::halo2::dev::MockProver;
     use halo2wrong::halo2::plonk::{create_proof, keygen_pk, keygen_vk, verify_proof};
     use halo2wrong::halo2::poly::commitment::ParamsProver;
     use halo2wrong::halo2::poly::kzg::commitment::{
@@ -433,6 +579,53 @@ mod tests {
         }
     }
 
+    // a dealing encrypted with the given randomness, whose instance is consistent with it
+    fn deal_with_random(
+        dkg_config: DkgConfig,
+        pks: Vec<GkG1>,
+        r: BnScalar,
+        mut rng: impl RngCore,
+    ) -> DkgMemberParams {
+        let mut dkg = DkgMemberParams::new(dkg_config, pks, &mut rng).unwrap();
+
+        let rs = bn_to_grumpkin_scalar(&r);
+        dkg.public_params.gr = (GkG1::generator() * rs).to_affine();
+        dkg.public_params.ciphers = dkg
+            .public_keys
+            .iter()
+            .zip(dkg.shares.expose_secret().iter())
+            .map(|(pk, s)| encryption_key(&(*pk * rs).to_affine()) + s)
+            .collect();
+        dkg.r = Secret::new(r);
+
+        dkg
+    }
+
+    #[test]
+    fn test_dkg_circuit_randomness() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let (pks, _) = mock_members(&dkg_config, &mut rng);
+        let run = |dkg: &DkgMemberParams, non_canonical: bool| {
+            let mut circuit = dkg.circuit(OsRng);
+            circuit.non_canonical_random = non_canonical;
+            let dimension = DimensionMeasurement::measure(&circuit).unwrap();
+            MockProver::run(dimension.k(), &circuit, dkg.instance())
+                .unwrap()
+                .verify()
+        };
+
+        // 5 + p fits in the bits of r, and the dealing is valid with the canonical bits of 5
+        let dkg = deal_with_random(dkg_config, pks.clone(), BnScalar::from(5), &mut rng);
+        assert!(run(&dkg, false).is_ok());
+        assert!(run(&dkg, true).is_err());
+
+        // r = 0 would reveal the shares, as g^r and pk^r are the identity
+        let dkg = deal_with_random(dkg_config, pks, BnScalar::zero(), &mut rng);
+        assert!(run(&dkg, false).is_err());
+    }
+
     #[test]
     #[ignore]
     fn test_pk_vk() {
@@ -665,6 +858,9 @@ mod tests {
         let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
 
         // simulation skips the snark proof and verify
+        for &dkg in dkgs_pub.iter() {
+            dkg.check_low_degree(&dkg_config, &mut rng).unwrap();
+        }
 
         #[cfg(not(feature = "g2chip"))]
         {
@@ -675,7 +871,22 @@ mod tests {
         }
 
         // compute public parameters
-        let pp = dkg_global_public_params(&dkgs_pub);
+        let pp = dkg_global_public_params(&dkg_config, &dkgs_pub).unwrap();
+        pp.check_low_degree(&dkg_config, &mut rng).unwrap();
+
+        // malformed dealings are rejected instead of indexed
+        let mut short = dkgs_pub[0].clone();
+        short.public_shares.pop();
+        let mut short_pub = dkgs_pub.clone();
+        short_pub[0] = &short;
+        assert!(matches!(
+            dkg_global_public_params(&dkg_config, &short_pub),
+            Err(Error::LengthMismatch { .. })
+        ));
+        assert!(dkg_global_public_params(&dkg_config, &[]).is_err());
+        short.public_shares = dkgs_pub[0].public_shares.clone();
+        short.ciphers.pop();
+        assert!(members[0].dkg_share_key(&dkg_config, 1, &[&short]).is_err());
 
         // each member decrypt to obtain their own shares
         let mut shares = vec![];
@@ -693,16 +904,21 @@ mod tests {
         let input = b"first random";
         let mut sigmas = vec![];
         for i in 0..number_of_members {
-            let sigma = shares[i].evaluate(input, &mut rng);
+            let sigma = shares[i].evaluate(&EvalDomain::default(), input, &mut rng);
             sigma
-                .verify(&dkg_config, input, &pp.verify_keys[i])
+                .verify(
+                    &dkg_config,
+                    &EvalDomain::default(),
+                    input,
+                    &pp.verify_keys[i],
+                )
                 .unwrap();
             sigmas.push(sigma);
         }
 
         // combine partial evaluations to obtain final random
         let v = combine_partial_evaluations(&dkg_config, &sigmas[0..threshold]).unwrap();
-        v.verify(input, &pp.g2a).unwrap();
+        v.verify(&EvalDomain::default(), input, &pp.g2a).unwrap();
     }
 
     #[test]
@@ -713,4 +929,91 @@ mod tests {
         mock_dvrf(42, 83);
         mock_dvrf(86, 171);
     }
+
+    #[test]
+    fn test_malformed_input() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let (pks, members) = mock_members(&dkg_config, &mut rng);
+        assert!(matches!(
+            DkgMemberParams::new(dkg_config, pks[0..4].to_vec(), &mut rng),
+            Err(Error::LengthMismatch {
+                expected: 5,
+                actual: 4
+            })
+        ));
+
+        let dkgs: Vec<_> = (0..3)
+            .map(|_| DkgMemberParams::new(dkg_config, pks.clone(), &mut rng).unwrap())
+            .collect();
+
+        #[cfg(feature = "g2chip")]
+        {
+            let instance = dkgs[0].instance()[0].clone();
+            let (pp, decoded_pks) =
+                DkgMemberPublicParams::from_instance(&dkg_config, &instance).unwrap();
+            assert_eq!(pp.ga, dkgs[0].public_params.ga);
+            assert_eq!(pp.g2a, dkgs[0].public_params.g2a);
+            assert_eq!(decoded_pks, pks);
+
+            assert!(matches!(
+                DkgMemberPublicParams::from_instance(&dkg_config, &instance[1..]),
+                Err(Error::MalformedInstance(_))
+            ));
+
+            // moving x off the curve
+            let mut tampered = instance.clone();
+            tampered[0] += BnScalar::one();
+            assert!(matches!(
+                DkgMemberPublicParams::from_instance(&dkg_config, &tampered),
+                Err(Error::InvalidPoint)
+            ));
+
+            // the first member public key
+            let mut tampered = instance.clone();
+            let begin = dkg_config.instance_size() - 2 * dkg_config.number_of_members();
+            tampered[begin + 1] += BnScalar::one();
+            assert!(matches!(
+                DkgMemberPublicParams::from_instance(&dkg_config, &tampered),
+                Err(Error::InvalidPoint)
+            ));
+        }
+
+        #[cfg(not(feature = "g2chip"))]
+        {
+            let instance = dkgs[0].instance()[0].clone();
+            let g2a = dkgs[0].public_params.g2a;
+            let (pp, decoded_pks) =
+                DkgMemberPublicParams::from_instance_with_g2a(&dkg_config, &instance, &g2a)
+                    .unwrap();
+            assert_eq!(pp.ga, dkgs[0].public_params.ga);
+            assert_eq!(pp.g2a, g2a);
+            assert_eq!(decoded_pks, pks);
+
+            // g2a of another dealer does not match the proven ga
+            let other = dkgs[1].public_params.g2a;
+            assert!(matches!(
+                DkgMemberPublicParams::from_instance_with_g2a(&dkg_config, &instance, &other),
+                Err(Error::VerifyFailed)
+            ));
+        }
+
+        let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
+        let sigmas: Vec<_> = (0..2)
+            .map(|i| {
+                members[i]
+                    .dkg_share_key(&dkg_config, i + 1, &dkgs_pub)
+                    .unwrap()
+                    .evaluate(&EvalDomain::default(), b"input", &mut rng)
+            })
+            .collect();
+        assert!(matches!(
+            combine_partial_evaluations(&dkg_config, &sigmas),
+            Err(Error::LengthMismatch {
+                expected: 3,
+                actual: 2
+            })
+        ));
+    }
 }
@@ -0,0 +1,100 @@
+// plan the degree of the kzg parameters for a DkgConfig and a kind of circuit: the circuit is laid
+// out without witnesses to measure its rows, and the key and memory sizes are estimated from the columns
+use crate::dkg::DkgConfig;
+use crate::error::Error;
+use crate::{CircuitKind, DkgCircuit};
+use halo2wrong::curves::bn256::Fr as BnScalar;
+use halo2wrong::halo2::plonk::{Circuit, ConstraintSystem};
+use halo2wrong::utils::DimensionMeasurement;
+
+// bytes of a field element and of an uncompressed G1 commitment
+const SCALAR_BYTES: u64 = 32;
+const POINT_BYTES: u64 = 64;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitPlan {
+    pub dkg_config: DkgConfig,
+    pub kind: CircuitKind,
+    // the minimum degree, i.e. the circuit has at most 2^k rows
+    pub k: u32,
+    pub instance_size: usize,
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub permutation_columns: usize,
+    pub lookups: usize,
+    // estimates in bytes
+    pub vk_bytes: u64,
+    pub pk_bytes: u64,
+    pub prover_memory_bytes: u64,
+}
+
+pub fn plan(dkg_config: DkgConfig, kind: CircuitKind) -> Result<CircuitPlan, Error> {
+    let circuit = DkgCircuit::dummy_of(dkg_config, kind);
+    let k = DimensionMeasurement::measure(&circuit)
+        .map_err(Error::Circuit)?
+        .k();
+
+    let mut cs = ConstraintSystem::<BnScalar>::default();
+    DkgCircuit::configure(&mut cs);
+
+    let advice_columns = cs.num_advice_columns();
+    // selectors are compressed into fixed columns by keygen, so this is an upper bound
+    let fixed_columns = cs.num_fixed_columns() + cs.num_selectors();
+    let permutation_columns = cs.permutation().get_columns().len();
+    let lookups = cs.lookups().len();
+
+    let n = 1u64 << k;
+    // the quotient is computed on the extended domain of size 2^k * 2^ceil(log2(degree - 1))
+    let extended = n * (cs.degree() as u64 - 1).next_power_of_two();
+
+    let key_columns = (fixed_columns + permutation_columns) as u64;
+    let vk_bytes = key_columns * POINT_BYTES;
+    // fixed and permutation polynomials as values, coefficients and extended cosets,
+    // and l_0, l_last and l_active on the extended domain
+    let pk_bytes = vk_bytes + (key_columns * (2 * n + extended) + 3 * extended) * SCALAR_BYTES;
+    // the prover also holds the advice and instance polynomials, the permuted input, the permuted
+    // table and the product of every lookup, and the quotient
+    let witness_columns = (advice_columns + cs.num_instance_columns() + 3 * lookups) as u64;
+    let prover_memory_bytes =
+        pk_bytes + (witness_columns * (2 * n + extended) + extended) * SCALAR_BYTES;
+
+    Ok(CircuitPlan {
+        dkg_config,
+        kind,
+        k,
+        instance_size: kind.instance_size(&dkg_config),
+        advice_columns,
+        fixed_columns,
+        permutation_columns,
+        lookups,
+        vk_bytes,
+        pk_bytes,
+        prover_memory_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let dkg = plan(dkg_config, CircuitKind::Dkg).unwrap();
+
+        // the degree used for (3, 5) in the README and the proof tests
+        assert!(dkg.k <= 18);
+        assert_eq!(dkg.instance_size, dkg_config.instance_size());
+        assert!(dkg.vk_bytes < dkg.pk_bytes);
+        assert!(dkg.pk_bytes < dkg.prover_memory_bytes);
+
+        // the compressed circuit hashes the public data in the circuit
+        let compressed = plan(dkg_config, CircuitKind::Compressed).unwrap();
+        assert_eq!(compressed.instance_size, 1);
+        assert!(compressed.k >= dkg.k);
+
+        let refresh = plan(dkg_config, CircuitKind::Refresh).unwrap();
+        assert_eq!(refresh.instance_size, dkg_config.refresh_instance_size());
+        assert!(refresh.k <= 18);
+    }
+}
@@ -31,12 +31,45 @@ impl Spec<Fr, 3, 2> for P128Pow5T3Bn {
     }
 }
 
+// the permutation of P128Pow5T3Bn as in halo2_gadgets, for sponges over inputs whose length
+// is only known at runtime
+pub(crate) fn permute(state: &mut [Fr; 3]) {
+    let (round_constants, mds, _) = P128Pow5T3Bn::constants();
+    let r_f = P128Pow5T3Bn::full_rounds() / 2;
+    let r_p = P128Pow5T3Bn::partial_rounds();
+
+    for (round, rcs) in round_constants.iter().take(2 * r_f + r_p).enumerate() {
+        for (word, rc) in state.iter_mut().zip(rcs.iter()) {
+            *word += rc;
+        }
+
+        if round < r_f || round >= r_f + r_p {
+            for word in state.iter_mut() {
+                *word = P128Pow5T3Bn::sbox(*word);
+            }
+        } else {
+            // in a partial round, the s-box is only applied to the first word
+            state[0] = P128Pow5T3Bn::sbox(state[0]);
+        }
+
+        let mut new_state = [Fr::zero(); 3];
+        for (word, row) in new_state.iter_mut().zip(mds.iter()) {
+            *word = row
+                .iter()
+                .zip(state.iter())
+                .fold(Fr::zero(), |acc, (m, s)| acc + *m * s);
+        }
+        *state = new_state;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use halo2_gadgets::poseidon::primitives::{ConstantLength, Hash};
     use halo2_gadgets::poseidon::{Hash as PoseidonHash, Pow5Chip, Pow5Config};
     use halo2wrong::curves::bn256::Fr as Fp;
+    use halo2wrong::curves::ff::PrimeField;
     use halo2wrong::halo2::circuit::{Layouter, SimpleFloorPlanner, Value};
     use halo2wrong::halo2::plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance};
     use halo2wrong::utils::{mock_prover_verify, DimensionMeasurement};
@@ -184,4 +217,17 @@ mod tests {
 
         mock_prover_verify(&circuit, vec![vec![output]]);
     }
+
+    #[test]
+    fn test_permute() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+
+        // a hash of constant length 2 is a single permutation with capacity 2 * 2^64
+        let message = [Fp::random(&mut rng), Fp::random(&mut rng)];
+        let output = Hash::<_, P128Pow5T3Bn, ConstantLength<2>, 3, 2>::init().hash(message);
+
+        let mut state = [message[0], message[1], Fp::from_u128(2 << 64)];
+        permute(&mut state);
+        assert_eq!(state[0], output);
+    }
 }
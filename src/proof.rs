@@ -0,0 +1,243 @@
+use crate::error::Error;
+use ark_std::{end_timer, start_timer};
+use halo2_solidity_verifier::Keccak256Transcript;
+use halo2wrong::curves::bn256::{Bn256, Fr as BnScalar, G1Affine as BnG1};
+use halo2wrong::halo2::plonk::{create_proof, verify_proof, Circuit, ProvingKey, VerifyingKey};
+use halo2wrong::halo2::poly::commitment::ParamsProver;
+use halo2wrong::halo2::poly::kzg::commitment::ParamsKZG;
+use halo2wrong::halo2::poly::VerificationStrategy;
+use halo2wrong::halo2::{
+    poly::kzg::{
+        multiopen::{ProverSHPLONK, VerifierSHPLONK},
+        strategy::{AccumulatorStrategy, SingleStrategy},
+    },
+    transcript::TranscriptWriterBuffer,
+};
+use rand_core::RngCore;
+
+// prove with the keccak transcript of the solidity verifier and check the proof before it is published
+pub fn create_proof_checked(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<BnG1>,
+    circuit: impl Circuit<BnScalar>,
+    instance: &[BnScalar],
+    mut rng: impl RngCore,
+) -> Result<Vec<u8>, Error> {
+    let proof = {
+        let mut transcript = Keccak256Transcript::new(Vec::new());
+        create_proof::<_, ProverSHPLONK<_>, _, _, _, _>(
+            params,
+            pk,
+            &[circuit],
+            &[&[instance]],
+            &mut rng,
+            &mut transcript,
+        )
+        .map_err(Error::Circuit)?;
+        transcript.finalize()
+    };
+
+    verify_single(
+        params.verifier_params(),
+        pk.get_vk(),
+        proof.as_slice(),
+        instance,
+    )?;
+
+    Ok(proof)
+}
+
+pub fn verify_single(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<BnG1>,
+    proof: &[u8],
+    instance: &[BnScalar],
+) -> Result<(), Error> {
+    let start = start_timer!(|| format!("verify proof"));
+    let result = {
+        let mut transcript = Keccak256Transcript::new(proof);
+        verify_proof::<_, VerifierSHPLONK<_>, _, _, SingleStrategy<_>>(
+            params,
+            vk,
+            SingleStrategy::new(params),
+            &[&[instance]],
+            &mut transcript,
+        )
+    };
+    end_timer!(start);
+
+    result.map_err(|_| Error::InvalidProof)
+}
+
+// verify the proofs of all dealers against the same vk with a single final pairing;
+// if the batch fails, the proofs are verified one at a time to find the invalid dealers
+pub fn verify_batch(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<BnG1>,
+    proofs: &[(&[u8], &[BnScalar])],
+) -> Result<(), Error> {
+    let start = start_timer!(|| format!("batch verify {} proofs", proofs.len()));
+    let batched = proofs.iter().try_fold(
+        AccumulatorStrategy::new(params),
+        |strategy, (proof, instance)| {
+            let mut transcript = Keccak256Transcript::new(*proof);
+            verify_proof::<_, VerifierSHPLONK<_>, _, _, AccumulatorStrategy<_>>(
+                params,
+                vk,
+                strategy,
+                &[&[*instance]],
+                &mut transcript,
+            )
+        },
+    );
+    let valid = batched.map(|strategy| strategy.finalize()).unwrap_or(false);
+    end_timer!(start);
+
+    if valid {
+        return Ok(());
+    }
+
+    // dealers are indexed from 1 in the order of the proofs
+    let dealers = proofs
+        .iter()
+        .enumerate()
+        .filter(|(_, (proof, instance))| verify_single(params, vk, proof, instance).is_err())
+        .map(|(i, _)| i + 1)
+        .collect();
+
+    Err(Error::InvalidProofs { dealers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2wrong::halo2::circuit::{Layouter, SimpleFloorPlanner, Value};
+    use halo2wrong::halo2::plonk::{
+        keygen_pk, keygen_vk, Advice, Column, ConstraintSystem, Error as PlonkError, Instance,
+        Selector,
+    };
+    use halo2wrong::halo2::poly::Rotation;
+    use rand_core::OsRng;
+
+    // proves knowledge of x with x^2 = instance, so that the proofs are cheap to create
+    #[derive(Clone, Default)]
+    struct SquareCircuit {
+        x: Value<BnScalar>,
+    }
+
+    #[derive(Clone)]
+    struct SquareConfig {
+        x: Column<Advice>,
+        square: Column<Advice>,
+        instance: Column<Instance>,
+        selector: Selector,
+    }
+
+    impl Circuit<BnScalar> for SquareCircuit {
+        type Config = SquareConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<BnScalar>) -> Self::Config {
+            let x = meta.advice_column();
+            let square = meta.advice_column();
+            let instance = meta.instance_column();
+            let selector = meta.selector();
+            meta.enable_equality(square);
+            meta.enable_equality(instance);
+
+            meta.create_gate("square", |meta| {
+                let s = meta.query_selector(selector);
+                let x = meta.query_advice(x, Rotation::cur());
+                let square = meta.query_advice(square, Rotation::cur());
+                vec![s * (x.clone() * x - square)]
+            });
+
+            SquareConfig {
+                x,
+                square,
+                instance,
+                selector,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<BnScalar>,
+        ) -> Result<(), PlonkError> {
+            let square = layouter.assign_region(
+                || "square",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    region.assign_advice(|| "x", config.x, 0, || self.x)?;
+                    region.assign_advice(|| "square", config.square, 0, || self.x.map(|x| x * x))
+                },
+            )?;
+
+            layouter.constrain_instance(square.cell(), config.instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let mut rng = OsRng;
+        let params = ParamsKZG::<Bn256>::setup(4, &mut rng);
+        let vk = keygen_vk(&params, &SquareCircuit::default()).expect("keygen_vk should not fail");
+        let pk =
+            keygen_pk(&params, vk, &SquareCircuit::default()).expect("keygen_pk should not fail");
+
+        let xs: Vec<_> = (1..=5).map(|x| BnScalar::from(x as u64)).collect();
+        let mut instances: Vec<_> = xs.iter().map(|x| vec![x * x]).collect();
+        let mut proofs: Vec<_> = xs
+            .iter()
+            .zip(instances.iter())
+            .map(|(x, instance)| {
+                let circuit = SquareCircuit {
+                    x: Value::known(*x),
+                };
+                create_proof_checked(&params, &pk, circuit, instance, &mut rng).unwrap()
+            })
+            .collect();
+
+        let batch: Vec<_> = proofs
+            .iter()
+            .zip(instances.iter())
+            .map(|(proof, instance)| (proof.as_slice(), instance.as_slice()))
+            .collect();
+        verify_batch(params.verifier_params(), pk.get_vk(), &batch).unwrap();
+
+        // the proof of dealer 2 is for another instance and the proof of dealer 5 is corrupted
+        instances[1][0] += BnScalar::from(1);
+        let last = proofs[4].len() - 1;
+        proofs[4][last] ^= 1;
+
+        let batch: Vec<_> = proofs
+            .iter()
+            .zip(instances.iter())
+            .map(|(proof, instance)| (proof.as_slice(), instance.as_slice()))
+            .collect();
+        let res = verify_batch(params.verifier_params(), pk.get_vk(), &batch);
+        assert!(matches!(res, Err(Error::InvalidProofs { dealers }) if dealers == vec![2, 5]));
+
+        assert!(verify_single(
+            params.verifier_params(),
+            pk.get_vk(),
+            &proofs[0],
+            &instances[0]
+        )
+        .is_ok());
+        assert!(verify_single(
+            params.verifier_params(),
+            pk.get_vk(),
+            &proofs[1],
+            &instances[1]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::complaint::Complaint;
+use crate::dkg::DkgConfig;
+use crate::error::Error;
+use crate::{
+    dkg_global_public_params, DkgGlobalPubParams, DkgMemberPublicParams, DkgShareKey, MemberKey,
+};
+use halo2wrong::curves::bn256::{Fr as BnScalar, G1Affine as BnG1};
+use halo2wrong::curves::group::Curve;
+use halo2wrong::curves::grumpkin::G1Affine as GkG1;
+use rand_core::RngCore;
+
+// the NIDKG is accepted if at least m dealers are qualified, where t < m <= n
+#[derive(Debug, Clone, Copy)]
+pub struct QualifiedPolicy {
+    min_dealers: usize,
+}
+
+impl QualifiedPolicy {
+    pub fn new(dkg_config: &DkgConfig, min_dealers: usize) -> Result<Self, Error> {
+        let valid = (min_dealers > dkg_config.threshold()
+            || min_dealers == dkg_config.number_of_members())
+            && min_dealers <= dkg_config.number_of_members();
+        if !valid {
+            return Err(Error::InvalidPolicy { min_dealers });
+        }
+
+        Ok(QualifiedPolicy { min_dealers })
+    }
+
+    // every member has to deal a valid contribution
+    pub fn all(dkg_config: &DkgConfig) -> Self {
+        QualifiedPolicy {
+            min_dealers: dkg_config.number_of_members(),
+        }
+    }
+
+    pub fn min_dealers(&self) -> usize {
+        self.min_dealers
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExclusionReason {
+    // the number of public shares or ciphers does not match the number of members
+    Malformed,
+    // ga and g2a do not have the same exponent
+    PublicCheckFailed,
+    // a valid complaint shows that the share dealt to the accuser is invalid
+    InvalidShare { accuser: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExcludedDealer {
+    // position of the dealer in the list of public parameters, starting from 1
+    pub dealer: usize,
+    pub reason: ExclusionReason,
+}
+
+// dealers are identified by their position in the list of public parameters, starting from 1
+#[derive(Debug, Clone)]
+pub struct QualifiedSet {
+    pub qualified: Vec<usize>,
+    pub excluded: Vec<ExcludedDealer>,
+}
+
+impl QualifiedSet {
+    // select the public parameters of the qualified dealers
+    pub fn select<'a>(&self, pps: &[&'a DkgMemberPublicParams]) -> Vec<&'a DkgMemberPublicParams> {
+        self.qualified.iter().map(|&i| pps[i - 1]).collect()
+    }
+}
+
+// output of deriving the secret share from the qualified dealers
+#[derive(Debug)]
+pub struct DkgDerivation {
+    pub share_key: DkgShareKey,
+    pub global_params: DkgGlobalPubParams,
+    pub qualified: QualifiedSet,
+    // complaints created by this member; they need to be published so that
+    // all the honest members agree on the same qualified set
+    pub complaints: Vec<Complaint>,
+}
+
+// derive the qualified set from public information only, so every member obtains the same result
+// given the same dealer list and complaints; invalid complaints are ignored
+pub fn qualified_dealers(
+    dkg_config: &DkgConfig,
+    pks: &[GkG1],
+    pps: &[&DkgMemberPublicParams],
+    complaints: &[Complaint],
+    policy: &QualifiedPolicy,
+) -> Result<QualifiedSet, Error> {
+    if pks.len() != dkg_config.number_of_members() {
+        return Err(Error::InvalidParams {
+            threshold: dkg_config.threshold(),
+            number_of_members: pks.len(),
+        });
+    }
+
+    let mut qualified = vec![];
+    let mut excluded = vec![];
+    for (i, &pp) in pps.iter().enumerate() {
+        let dealer = i + 1;
+
+        if pp.check_lengths(dkg_config).is_err() {
+            excluded.push(ExcludedDealer {
+                dealer,
+                reason: ExclusionReason::Malformed,
+            });
+            continue;
+        }
+
+        if pp.check_public().is_err() {
+            excluded.push(ExcludedDealer {
+                dealer,
+                reason: ExclusionReason::PublicCheckFailed,
+            });
+            continue;
+        }
+
+        // the complaint with the smallest accuser index is reported
+        let accuser = complaints
+            .iter()
+            .filter(|c| c.dealer == dealer && c.verify(dkg_config, pks, pp).is_ok())
+            .map(|c| c.accuser)
+            .min();
+        if let Some(accuser) = accuser {
+            excluded.push(ExcludedDealer {
+                dealer,
+                reason: ExclusionReason::InvalidShare { accuser },
+            });
+            continue;
+        }
+
+        qualified.push(dealer);
+    }
+
+    if qualified.len() < policy.min_dealers() {
+        return Err(Error::NotEnoughDealers {
+            qualified: qualified.len(),
+            required: policy.min_dealers(),
+        });
+    }
+
+    Ok(QualifiedSet {
+        qualified,
+        excluded,
+    })
+}
+
+impl MemberKey {
+    // check the shares dealt to this member, complain about the invalid ones,
+    // and derive the secret share and global public parameters from the qualified dealers
+    pub fn derive_qualified(
+        &self,
+        dkg_config: &DkgConfig,
+        index: usize,
+        pks: &[GkG1],
+        pps: &[&DkgMemberPublicParams],
+        complaints: &[Complaint],
+        policy: &QualifiedPolicy,
+        mut rng: impl RngCore,
+    ) -> Result<DkgDerivation, Error> {
+        if index < 1 || index > dkg_config.number_of_members() {
+            return Err(Error::InvalidIndex { index });
+        }
+
+        // malformed dealings are excluded publicly; no complaint is needed for them
+        let mut own_complaints = vec![];
+        for (i, &pp) in pps.iter().enumerate() {
+            if pp.check_lengths(dkg_config).is_err() {
+                continue;
+            }
+            if let Err(Error::InvalidShare { .. }) = self.verify_share(dkg_config, index, pp) {
+                own_complaints.push(self.complain(index, i + 1, pp, &mut rng));
+            }
+        }
+
+        let all_complaints: Vec<_> = complaints
+            .iter()
+            .chain(own_complaints.iter())
+            .cloned()
+            .collect();
+        let qualified = qualified_dealers(dkg_config, pks, pps, &all_complaints, policy)?;
+        let qualified_pps = qualified.select(pps);
+
+        let k = index - 1;
+        let mut sk = BnScalar::zero();
+        for &pp in qualified_pps.iter() {
+            sk += self.decrypt_share(&pp.gr, &pp.ciphers[k]);
+        }
+        let vk = (BnG1::generator() * sk).to_affine();
+        let share_key = DkgShareKey::new(index, sk, vk);
+
+        let global_params = dkg_global_public_params(dkg_config, &qualified_pps)?;
+        share_key.verify(dkg_config, &global_params.verify_keys)?;
+
+        Ok(DkgDerivation {
+            share_key,
+            global_params,
+            qualified,
+            complaints: own_complaints,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DkgMemberParams;
+    use halo2wrong::curves::bn256::G2Affine as BnG2;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_qualified_set() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(2, 5).unwrap();
+        let members: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| MemberKey::random(&mut rng))
+            .collect();
+        let pks: Vec<_> = members.iter().map(|m| m.public_key()).collect();
+
+        let mut dkgs: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| DkgMemberParams::new(dkg_config, pks.clone(), &mut rng).unwrap())
+            .collect();
+
+        // dealer 2 sends a bad share to member 3, dealer 4 publishes an inconsistent g2a
+        dkgs[1].public_params.ciphers[2] += BnScalar::one();
+        dkgs[3].public_params.g2a = BnG2::random(&mut rng);
+
+        let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
+
+        assert!(QualifiedPolicy::new(&dkg_config, 2).is_err());
+        let policy = QualifiedPolicy::new(&dkg_config, 4).unwrap();
+        let derivation = members[2]
+            .derive_qualified(&dkg_config, 3, &pks, &dkgs_pub, &[], &policy, &mut rng)
+            .unwrap_err();
+        assert!(matches!(
+            derivation,
+            Error::NotEnoughDealers {
+                qualified: 3,
+                required: 4
+            }
+        ));
+
+        let policy = QualifiedPolicy::new(&dkg_config, 3).unwrap();
+        let accuser = members[2]
+            .derive_qualified(&dkg_config, 3, &pks, &dkgs_pub, &[], &policy, &mut rng)
+            .unwrap();
+        assert_eq!(accuser.qualified.qualified, vec![1, 3, 5]);
+        assert_eq!(accuser.complaints.len(), 1);
+
+        // other members reach the same qualified set once the complaint is published
+        for (i, member) in members.iter().enumerate() {
+            let derivation = member
+                .derive_qualified(
+                    &dkg_config,
+                    i + 1,
+                    &pks,
+                    &dkgs_pub,
+                    &accuser.complaints,
+                    &policy,
+                    &mut rng,
+                )
+                .unwrap();
+            assert_eq!(derivation.qualified.qualified, accuser.qualified.qualified);
+            assert_eq!(
+                derivation.qualified.excluded,
+                vec![
+                    ExcludedDealer {
+                        dealer: 2,
+                        reason: ExclusionReason::InvalidShare { accuser: 3 }
+                    },
+                    ExcludedDealer {
+                        dealer: 4,
+                        reason: ExclusionReason::PublicCheckFailed
+                    },
+                ]
+            );
+            assert_eq!(derivation.global_params.ga, accuser.global_params.ga);
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::dkg::DkgConfig;
+use crate::error::Error;
+use crate::utils::{check_length, point_to_public, public_to_grumpkin, public_to_point, rns_setup};
+use crate::{
+    DkgCircuit, DkgGlobalPubParams, DkgMemberParams, DkgMemberPublicParams, DkgShareKey, MemberKey,
+    POINT_LEN,
+};
+use halo2wrong::curves::bn256::{Fr as BnScalar, G1Affine as BnG1, G2Affine as BnG2};
+use halo2wrong::curves::group::prime::PrimeCurveAffine;
+use halo2wrong::curves::group::Curve;
+use halo2wrong::curves::grumpkin::G1Affine as GkG1;
+use halo2wrong::curves::CurveAffine;
+use halo2wrong::halo2::arithmetic::Field;
+use halo2wrong::halo2::circuit::Value;
+use rand_core::RngCore;
+use std::rc::Rc;
+
+impl DkgMemberParams {
+    // deal a random polynomial with zero constant term; adding its shares to the existing
+    // secret shares re-randomises them while ga and g2a stay the same
+    pub fn new_refresh(
+        dkg_config: DkgConfig,
+        public_keys: Vec<GkG1>,
+        mut rng: impl RngCore,
+    ) -> Result<Self, Error> {
+        check_length(dkg_config.number_of_members(), public_keys.len())?;
+
+        // with threshold 1 the polynomial is constant zero and every share would be zero
+        if dkg_config.threshold() < 2 {
+            return Err(Error::InvalidParams {
+                threshold: dkg_config.threshold(),
+                number_of_members: dkg_config.number_of_members(),
+            });
+        }
+
+        let mut coeffs: Vec<_> = (0..dkg_config.threshold())
+            .map(|_| BnScalar::random(&mut rng))
+            .collect();
+        coeffs[0] = BnScalar::zero();
+
+        Ok(Self::deal(dkg_config, coeffs, public_keys, rng))
+    }
+
+    pub fn refresh_circuit(&self, mut rng: impl RngCore) -> DkgCircuit {
+        let coeffs: Vec<_> = self
+            .coeffs
+            .expose_secret()
+            .iter()
+            .map(|a| Value::known(*a))
+            .collect();
+        let public_keys: Vec<_> = self
+            .public_keys
+            .iter()
+            .map(|pk| Value::known(*pk))
+            .collect();
+
+        let grumpkin_aux_generator = Value::known(GkG1::random(&mut rng));
+        DkgCircuit::new_refresh(
+            self.dkg_config,
+            coeffs,
+            Value::known(*self.r.expose_secret()),
+            public_keys,
+            grumpkin_aux_generator,
+        )
+    }
+
+    pub fn refresh_instance(&self) -> Vec<Vec<BnScalar>> {
+        self.public_params.refresh_instance(&self.public_keys)
+    }
+}
+
+impl DkgMemberPublicParams {
+    // same as instance() without ga and g2a
+    pub fn refresh_instance(&self, pks: &[GkG1]) -> Vec<Vec<BnScalar>> {
+        let (rns_base, _) = rns_setup::<BnG1>(0);
+        let rns_base = Rc::new(rns_base);
+
+        let mut public_data = vec![];
+        for i in 0..pks.len() {
+            let gs_public = point_to_public(Rc::clone(&rns_base), self.public_shares[i]);
+            public_data.extend(gs_public);
+        }
+
+        public_data.push(self.gr.x);
+        public_data.push(self.gr.y);
+
+        for c in self.ciphers.iter() {
+            public_data.push(*c);
+        }
+
+        for i in 0..pks.len() {
+            public_data.push(pks[i].x);
+            public_data.push(pks[i].y);
+        }
+
+        vec![public_data]
+    }
+
+    // check that the dealing has a share for every member and deals the zero secret,
+    // i.e. the identity and the public shares lie on a polynomial of degree less than threshold
+    pub fn check_refresh(&self, dkg_config: &DkgConfig, rng: impl RngCore) -> Result<(), Error> {
+        self.check_lengths(dkg_config)?;
+
+        if !bool::from(self.ga.is_identity()) || !bool::from(self.g2a.is_identity()) {
+            return Err(Error::VerifyFailed);
+        }
+
+        self.check_low_degree(dkg_config, rng)
+    }
+
+    pub fn from_refresh_instance(
+        dkg_config: &DkgConfig,
+        instance: &[BnScalar],
+    ) -> Result<(Self, Vec<GkG1>), Error> {
+        let len = dkg_config.refresh_instance_size();
+        if len != instance.len() {
+            return Err(Error::MalformedInstance(format!(
+                "expected {len} elements, got {}",
+                instance.len()
+            )));
+        }
+
+        // read gs1,..., gs_n
+        let mut public_shares: Vec<BnG1> = vec![];
+        let mut begin = 0;
+        for _ in 0..dkg_config.number_of_members() {
+            let gs: BnG1 = public_to_point(&instance[begin..begin + POINT_LEN])?;
+            public_shares.push(gs);
+            begin += POINT_LEN;
+        }
+
+        // read gr
+        let gr = public_to_grumpkin(instance[begin], instance[begin + 1])?;
+        begin += 2;
+
+        // read cipher_1, ..., cipher_n
+        let mut ciphers = vec![];
+        for _ in 0..dkg_config.number_of_members() {
+            ciphers.push(instance[begin]);
+            begin += 1;
+        }
+
+        // read pk_1, ..., pk_n
+        let mut pks = vec![];
+        for _ in 0..dkg_config.number_of_members() {
+            let pk = public_to_grumpkin(instance[begin], instance[begin + 1])?;
+            pks.push(pk);
+            begin += 2;
+        }
+
+        // the circuit constrains the dealt secret to be zero
+        let pp = Self {
+            public_shares,
+            ciphers,
+            gr,
+            ga: BnG1::identity(),
+            g2a: BnG2::identity(),
+        };
+
+        Ok((pp, pks))
+    }
+}
+
+// update the verification keys with the refresh dealings; ga and g2a are unchanged
+pub fn refresh_global_public_params(
+    dkg_config: &DkgConfig,
+    gpp: &DkgGlobalPubParams,
+    pps: &[&DkgMemberPublicParams],
+    mut rng: impl RngCore,
+) -> Result<DkgGlobalPubParams, Error> {
+    check_length(dkg_config.number_of_members(), gpp.verify_keys.len())?;
+
+    let mut vks: Vec<_> = gpp.verify_keys.iter().map(|vk| vk.to_curve()).collect();
+    for &pp in pps.iter() {
+        pp.check_refresh(dkg_config, &mut rng)?;
+        for (vk, gs) in vks.iter_mut().zip(pp.public_shares.iter()) {
+            *vk = *vk + gs;
+        }
+    }
+
+    Ok(DkgGlobalPubParams {
+        ga: gpp.ga,
+        g2a: gpp.g2a,
+        verify_keys: vks.iter().map(|vk| vk.to_affine()).collect(),
+    })
+}
+
+impl MemberKey {
+    // decrypt and check the refresh shares for this member and move its share key to the next epoch
+    pub fn refresh_share_key(
+        &self,
+        dkg_config: &DkgConfig,
+        share: &DkgShareKey,
+        pps: &[&DkgMemberPublicParams],
+        mut rng: impl RngCore,
+    ) -> Result<DkgShareKey, Error> {
+        let index = share.index();
+
+        let mut delta = BnScalar::zero();
+        for &pp in pps.iter() {
+            pp.check_refresh(dkg_config, &mut rng)?;
+            delta += self.verify_share(dkg_config, index, pp)?;
+        }
+
+        Ok(share.refresh(delta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::{combine_partial_evaluations, EvalDomain};
+    use crate::dkg_global_public_params;
+    use halo2wrong::halo2::dev::MockProver;
+    use halo2wrong::utils::{mock_prover_verify, DimensionMeasurement};
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_refresh_circuit() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let pks: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| MemberKey::random(&mut rng).public_key())
+            .collect();
+
+        let refresh = DkgMemberParams::new_refresh(dkg_config, pks.clone(), &mut rng).unwrap();
+        let circuit = refresh.refresh_circuit(&mut rng);
+        let instance = refresh.refresh_instance();
+        assert_eq!(instance[0].len(), dkg_config.refresh_instance_size());
+        mock_prover_verify(&circuit, instance.clone());
+
+        let (pp, decoded_pks) =
+            DkgMemberPublicParams::from_refresh_instance(&dkg_config, &instance[0]).unwrap();
+        pp.check_refresh(&dkg_config, &mut rng).unwrap();
+        assert_eq!(pp.public_shares, refresh.public_params.public_shares);
+        assert_eq!(decoded_pks, pks);
+
+        // a dealing with non-zero constant term cannot be proven as a refresh
+        let dkg = DkgMemberParams::new(dkg_config, pks, &mut rng).unwrap();
+        let circuit = dkg.refresh_circuit(&mut rng);
+        let instance = dkg.refresh_instance();
+        let dimension = DimensionMeasurement::measure(&circuit).unwrap();
+        let prover = MockProver::run(dimension.k(), &circuit, instance).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_refresh() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let members: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| MemberKey::random(&mut rng))
+            .collect();
+        let pks: Vec<_> = members.iter().map(|m| m.public_key()).collect();
+
+        let dkgs: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| DkgMemberParams::new(dkg_config, pks.clone(), &mut rng).unwrap())
+            .collect();
+        let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
+        let gpp = dkg_global_public_params(&dkg_config, &dkgs_pub).unwrap();
+
+        let shares: Vec<_> = members
+            .iter()
+            .enumerate()
+            .map(|(i, m)| m.dkg_share_key(&dkg_config, i + 1, &dkgs_pub).unwrap())
+            .collect();
+
+        let input = b"refresh";
+        let sigmas: Vec<_> = shares[0..3]
+            .iter()
+            .map(|s| s.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        let v = combine_partial_evaluations(&dkg_config, &sigmas).unwrap();
+
+        // every member deals a refresh of the shares
+        let refreshes: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| DkgMemberParams::new_refresh(dkg_config, pks.clone(), &mut rng).unwrap())
+            .collect();
+        let refreshes_pub: Vec<_> = refreshes.iter().map(|r| r.member_public_params()).collect();
+
+        let new_gpp =
+            refresh_global_public_params(&dkg_config, &gpp, &refreshes_pub, &mut rng).unwrap();
+        assert_eq!(new_gpp.ga, gpp.ga);
+        assert_eq!(new_gpp.g2a, gpp.g2a);
+
+        let new_shares: Vec<_> = members
+            .iter()
+            .zip(shares.iter())
+            .map(|(m, s)| {
+                m.refresh_share_key(&dkg_config, s, &refreshes_pub, &mut rng)
+                    .unwrap()
+            })
+            .collect();
+        for (old, new) in shares.iter().zip(new_shares.iter()) {
+            assert_eq!(new.epoch(), old.epoch() + 1);
+            assert_ne!(new.secret_key(), old.secret_key());
+            new.verify(&dkg_config, &new_gpp.verify_keys).unwrap();
+        }
+
+        // refreshed shares produce the same pseudorandom value for the same gpk
+        let sigmas: Vec<_> = new_shares[2..5]
+            .iter()
+            .map(|s| s.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        for sigma in sigmas.iter() {
+            sigma
+                .verify(
+                    &dkg_config,
+                    &EvalDomain::default(),
+                    input,
+                    &new_gpp.verify_keys[sigma.index - 1],
+                )
+                .unwrap();
+        }
+        let new_v = combine_partial_evaluations(&dkg_config, &sigmas).unwrap();
+        new_v
+            .verify(&EvalDomain::default(), input, &new_gpp.g2a)
+            .unwrap();
+        assert_eq!(new_v.value, v.value);
+
+        // a regular dealing is rejected as a refresh
+        let mut mixed = refreshes_pub.clone();
+        mixed[0] = dkgs_pub[0];
+        assert!(refresh_global_public_params(&dkg_config, &gpp, &mixed, &mut rng).is_err());
+
+        // public shares of a non-zero constant with the identity as ga are rejected
+        // before any proof is verified
+        let mut forged = dkgs_pub[0].clone();
+        forged.ga = BnG1::identity();
+        forged.g2a = BnG2::identity();
+        assert!(matches!(
+            forged.check_refresh(&dkg_config, &mut rng),
+            Err(Error::InvalidDegree)
+        ));
+        let mut forged_pub = refreshes_pub.clone();
+        forged_pub[0] = &forged;
+        assert!(members[0]
+            .refresh_share_key(&dkg_config, &shares[0], &forged_pub, &mut rng)
+            .is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::dkg::{check_indices, lagrange_coefficients, DkgConfig};
+use crate::error::Error;
+use crate::utils::check_length;
+use crate::{DkgGlobalPubParams, DkgMemberParams, DkgMemberPublicParams, DkgShareKey, MemberKey};
+use halo2wrong::curves::bn256::{
+    Fr as BnScalar, G1Affine as BnG1, G1 as BnG1Curve, G2 as BnG2Curve,
+};
+use halo2wrong::curves::group::{Curve, Group};
+use halo2wrong::curves::grumpkin::G1Affine as GkG1;
+use halo2wrong::halo2::arithmetic::Field;
+use rand_core::RngCore;
+
+// a dealing of the secret share held by member "dealer" of the old committee
+#[derive(Debug, Clone, Copy)]
+pub struct ReshareDealing<'a> {
+    // index of the dealer in the old committee, between 1...NUMBER_OF_MEMBERS
+    pub dealer: usize,
+    pub public_params: &'a DkgMemberPublicParams,
+}
+
+impl DkgMemberParams {
+    // deal the secret share of the old committee as the constant term of a random polynomial
+    // to a new committee; the circuit exposes ga = g^sk, which has to be equal to the dealer's
+    // old verification key, so the same circuit and instance are used as in the NIDKG
+    pub fn new_reshare(
+        dkg_config: DkgConfig,
+        share: &DkgShareKey,
+        public_keys: Vec<GkG1>,
+        mut rng: impl RngCore,
+    ) -> Result<Self, Error> {
+        check_length(dkg_config.number_of_members(), public_keys.len())?;
+
+        let mut coeffs: Vec<_> = (0..dkg_config.threshold())
+            .map(|_| BnScalar::random(&mut rng))
+            .collect();
+        coeffs[0] = *share.secret_key();
+
+        Ok(Self::deal(dkg_config, coeffs, public_keys, rng))
+    }
+}
+
+impl DkgMemberPublicParams {
+    // check a resharing dealing for the new committee against the dealer's old verification key
+    pub fn check_reshare(&self, dkg_config: &DkgConfig, old_vk: &BnG1) -> Result<(), Error> {
+        self.check_lengths(dkg_config)?;
+
+        if self.ga != *old_vk {
+            return Err(Error::VerifyFailed);
+        }
+
+        self.check_public()
+    }
+}
+
+fn check_dealings(
+    old_config: &DkgConfig,
+    old_gpp: &DkgGlobalPubParams,
+    new_config: &DkgConfig,
+    dealings: &[ReshareDealing],
+) -> Result<Vec<BnScalar>, Error> {
+    if dealings.len() < old_config.threshold() {
+        return Err(Error::NotEnoughDealers {
+            qualified: dealings.len(),
+            required: old_config.threshold(),
+        });
+    }
+
+    // the dealers are looked up by index in the old verification keys
+    check_length(old_config.number_of_members(), old_gpp.verify_keys.len())?;
+
+    let indices: Vec<_> = dealings.iter().map(|d| d.dealer).collect();
+    check_indices(old_config.number_of_members(), &indices)?;
+
+    for d in dealings.iter() {
+        d.public_params
+            .check_reshare(new_config, &old_gpp.verify_keys[d.dealer - 1])?;
+    }
+
+    Ok(lagrange_coefficients(&indices))
+}
+
+// combine the dealings from at least t old members (sorted by dealer index) into the global
+// public parameters of the new committee; ga and g2a are the same as for the old committee
+pub fn reshare_global_public_params(
+    old_config: &DkgConfig,
+    old_gpp: &DkgGlobalPubParams,
+    new_config: &DkgConfig,
+    dealings: &[ReshareDealing],
+) -> Result<DkgGlobalPubParams, Error> {
+    let lambdas = check_dealings(old_config, old_gpp, new_config, dealings)?;
+
+    let mut ga = BnG1Curve::identity();
+    let mut g2a = BnG2Curve::identity();
+    let mut vks = vec![BnG1Curve::identity(); new_config.number_of_members()];
+    for (d, lambda) in dealings.iter().zip(lambdas.iter()) {
+        let pp = d.public_params;
+        ga = ga + pp.ga * lambda;
+        g2a = g2a + pp.g2a * lambda;
+        for (vk, gs) in vks.iter_mut().zip(pp.public_shares.iter()) {
+            *vk = *vk + gs * lambda;
+        }
+    }
+
+    if ga.to_affine() != old_gpp.ga || g2a.to_affine() != old_gpp.g2a {
+        return Err(Error::VerifyFailed);
+    }
+
+    Ok(DkgGlobalPubParams {
+        ga: old_gpp.ga,
+        g2a: old_gpp.g2a,
+        verify_keys: vks.iter().map(|vk| vk.to_affine()).collect(),
+    })
+}
+
+impl MemberKey {
+    // decrypt the shares dealt to member index of the new committee and combine them
+    // with Lagrange coefficients into a share of the same secret
+    pub fn reshare_share_key(
+        &self,
+        old_config: &DkgConfig,
+        old_gpp: &DkgGlobalPubParams,
+        new_config: &DkgConfig,
+        index: usize,
+        dealings: &[ReshareDealing],
+    ) -> Result<DkgShareKey, Error> {
+        let lambdas = check_dealings(old_config, old_gpp, new_config, dealings)?;
+
+        let mut sk = BnScalar::zero();
+        for (d, lambda) in dealings.iter().zip(lambdas.iter()) {
+            sk += self.verify_share(new_config, index, d.public_params)? * lambda;
+        }
+
+        let vk = (BnG1::generator() * sk).to_affine();
+        Ok(DkgShareKey::new(index, sk, vk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::{combine_partial_evaluations, EvalDomain};
+    use crate::dkg_global_public_params;
+    use rand_core::OsRng;
+
+    fn mock_committee(
+        dkg_config: &DkgConfig,
+        mut rng: impl RngCore,
+    ) -> (Vec<MemberKey>, Vec<GkG1>) {
+        let members: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| MemberKey::random(&mut rng))
+            .collect();
+        let pks = members.iter().map(|m| m.public_key()).collect();
+        (members, pks)
+    }
+
+    #[test]
+    fn test_reshare() {
+        let mut rng = OsRng;
+
+        let old_config = DkgConfig::new(3, 5).unwrap();
+        let (old_members, old_pks) = mock_committee(&old_config, &mut rng);
+        let dkgs: Vec<_> = (0..old_config.number_of_members())
+            .map(|_| DkgMemberParams::new(old_config, old_pks.clone(), &mut rng).unwrap())
+            .collect();
+        let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
+        let old_gpp = dkg_global_public_params(&old_config, &dkgs_pub).unwrap();
+        let old_shares: Vec<_> = old_members
+            .iter()
+            .enumerate()
+            .map(|(i, m)| m.dkg_share_key(&old_config, i + 1, &dkgs_pub).unwrap())
+            .collect();
+
+        let input = b"reshare";
+        let sigmas: Vec<_> = old_shares[0..3]
+            .iter()
+            .map(|s| s.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        let v = combine_partial_evaluations(&old_config, &sigmas).unwrap();
+
+        // old members 1, 2 and 4 reshare to a new committee of a different size
+        let new_config = DkgConfig::new(4, 7).unwrap();
+        let (new_members, new_pks) = mock_committee(&new_config, &mut rng);
+        let dealers = [1, 2, 4];
+        let reshares: Vec<_> = dealers
+            .iter()
+            .map(|&i| {
+                DkgMemberParams::new_reshare(
+                    new_config,
+                    &old_shares[i - 1],
+                    new_pks.clone(),
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect();
+        let dealings: Vec<_> = dealers
+            .iter()
+            .zip(reshares.iter())
+            .map(|(&dealer, r)| ReshareDealing {
+                dealer,
+                public_params: r.member_public_params(),
+            })
+            .collect();
+
+        let new_gpp =
+            reshare_global_public_params(&old_config, &old_gpp, &new_config, &dealings).unwrap();
+        assert_eq!(new_gpp.ga, old_gpp.ga);
+        assert_eq!(new_gpp.g2a, old_gpp.g2a);
+
+        let new_shares: Vec<_> = new_members
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                m.reshare_share_key(&old_config, &old_gpp, &new_config, i + 1, &dealings)
+                    .unwrap()
+            })
+            .collect();
+        for share in new_shares.iter() {
+            share.verify(&new_config, &new_gpp.verify_keys).unwrap();
+        }
+
+        // the new committee generates the same pseudorandom value
+        let sigmas: Vec<_> = new_shares[3..7]
+            .iter()
+            .map(|s| s.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        let new_v = combine_partial_evaluations(&new_config, &sigmas).unwrap();
+        new_v
+            .verify(&EvalDomain::default(), input, &old_gpp.g2a)
+            .unwrap();
+        assert_eq!(new_v.value, v.value);
+
+        // too few dealings
+        assert!(
+            reshare_global_public_params(&old_config, &old_gpp, &new_config, &dealings[0..2])
+                .is_err()
+        );
+
+        // a dealing whose constant term does not match the dealer's verification key
+        let fake = DkgMemberParams::new(new_config, new_pks, &mut rng).unwrap();
+        let mut bad_dealings = dealings.clone();
+        bad_dealings[1].public_params = fake.member_public_params();
+        assert!(
+            reshare_global_public_params(&old_config, &old_gpp, &new_config, &bad_dealings)
+                .is_err()
+        );
+
+        // old global public parameters without a verification key for every old member
+        let mut short_gpp = old_gpp.clone();
+        short_gpp.verify_keys.truncate(3);
+        assert!(matches!(
+            reshare_global_public_params(&old_config, &short_gpp, &new_config, &dealings),
+            Err(Error::LengthMismatch {
+                expected: 5,
+                actual: 3
+            })
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+// wrappers for secret key material: the value is wiped when dropped, Debug never prints it
+// and it is only readable through expose_secret()
+use halo2wrong::curves::bn256::Fr as BnScalar;
+use halo2wrong::curves::grumpkin::Fr as GkScalar;
+use static_assertions::assert_eq_size;
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+// the types that can be held by Secret; the trait is not exported, so it is sealed
+pub trait Wipe {
+    fn wipe(&mut self);
+}
+
+// the field elements of halo2curves do not implement Zeroize, so their limbs are wiped as bytes
+macro_rules! wipe_field {
+    ($ty:ty) => {
+        assert_eq_size!($ty, [u8; 32]);
+
+        impl Wipe for $ty {
+            fn wipe(&mut self) {
+                // SAFETY: the field element is a plain array of four u64 limbs of 32 bytes (checked
+                // above), so it can be written as bytes; u8 has no alignment requirement and all zero
+                // limbs are the valid representation of zero
+                let bytes = unsafe { &mut *(self as *mut $ty as *mut [u8; 32]) };
+                bytes.zeroize();
+            }
+        }
+    };
+}
+
+wipe_field!(BnScalar);
+wipe_field!(GkScalar);
+
+// only the current buffer is wiped, copies left behind by earlier reallocations are not
+impl Wipe for Vec<BnScalar> {
+    fn wipe(&mut self) {
+        self.iter_mut().for_each(|s| s.wipe());
+        self.clear();
+    }
+}
+
+macro_rules! wipe_zeroize {
+    ($ty:ty) => {
+        impl Wipe for $ty {
+            fn wipe(&mut self) {
+                self.zeroize();
+            }
+        }
+    };
+}
+
+wipe_zeroize!(String);
+wipe_zeroize!(Vec<u8>);
+wipe_zeroize!(Vec<String>);
+
+impl<const N: usize> Wipe for [u8; N] {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[derive(Clone)]
+pub struct Secret<T: Wipe>(T);
+
+impl<T: Wipe> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+
+    pub fn expose_secret_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Wipe> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl<T: Wipe> Zeroize for Secret<T> {
+    fn zeroize(&mut self) {
+        self.0.wipe();
+    }
+}
+
+impl<T: Wipe> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
+impl<T: Wipe> ZeroizeOnDrop for Secret<T> {}
+
+impl<T: Wipe> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2wrong::halo2::arithmetic::Field;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_secret() {
+        let mut rng = OsRng;
+        let x = BnScalar::random(&mut rng);
+        let mut secret = Secret::new(vec![x, x + BnScalar::one()]);
+        assert_eq!(secret.expose_secret()[0], x);
+
+        let debug = format!("{:?}", secret);
+        assert_eq!(debug, "Secret([REDACTED])");
+        assert!(!debug.contains(&format!("{:?}", x)));
+
+        let member = crate::MemberKey::random(&mut rng);
+        let debug = format!("{:?}", member);
+        assert!(!debug.contains(&format!("{:?}", member.secret_key())));
+
+        secret.zeroize();
+        assert!(secret.expose_secret().is_empty());
+        let mut y = Secret::new(x);
+        y.zeroize();
+        assert_eq!(*y.expose_secret(), BnScalar::zero());
+        let mut s = Secret::new(String::from("0x1234"));
+        s.zeroize();
+        assert!(s.expose_secret().is_empty());
+    }
+}
@@ -0,0 +1,699 @@
+// hex JSON encoding of the library types, the same format written by the client;
+// field elements are big endian hex strings with prefix "0x" and points are checked
+// to be on the curve (and in the prime order subgroup for Bn256 G2) when decoded
+use crate::complaint::{Complaint, DleqProof};
+use crate::dkg::{
+    BatchableEvalProof, BatchablePartialEval, DkgShareKey, PartialEval, PartialEvalProof,
+    PseudoRandom,
+};
+use crate::error::Error;
+use crate::secret::{Secret, Wipe};
+use crate::utils::is_in_g2_subgroup;
+use crate::{
+    DkgGlobalPubParams, DkgMemberParams, DkgMemberPublicParams, MemberKey, Signature,
+    WeightedConfig,
+};
+use halo2wrong::curves::bn256::{Fq, Fq2, Fr as BnScalar, G1Affine as BnG1, G2Affine as BnG2};
+use halo2wrong::curves::ff::PrimeField;
+use halo2wrong::curves::group::Curve;
+use halo2wrong::curves::grumpkin::G1Affine as GkG1;
+use halo2wrong::curves::CurveAffine;
+use hex::{decode, encode};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn le_bytes_to_hex(bytes: [u8; 32]) -> String {
+    // convert bytes in little endian to hex string with prefix "0x"
+    let reverse: Vec<_> = bytes.into_iter().rev().collect();
+    let hex_string = encode(&reverse).to_lowercase();
+    format!("0x{}", hex_string)
+}
+
+pub fn hex_to_le_bytes(s: &str) -> Result<[u8; 32], Error> {
+    let trimmed = s.strip_prefix("0x").unwrap_or(s);
+
+    let bytes =
+        decode(trimmed).map_err(|_| Error::InvalidEncoding(format!("invalid hex string {s}")))?;
+    if bytes.len() > 32 {
+        return Err(Error::InvalidEncoding(format!(
+            "hex string {s} exceeds 32 bytes"
+        )));
+    }
+
+    // Pad to 32 bytes with zeros
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(&bytes);
+    padded.reverse();
+    Ok(padded)
+}
+
+// decode a canonical field element, both Bn256 and Grumpkin fields use 32 bytes little endian
+fn hex_to_field<F: PrimeField<Repr = [u8; 32]>>(s: &str) -> Result<F, Error> {
+    let bytes = hex_to_le_bytes(s)?;
+    Option::from(F::from_repr(bytes))
+        .ok_or_else(|| Error::InvalidEncoding(format!("{s} is not a canonical field element")))
+}
+
+fn field_to_hex<F: PrimeField<Repr = [u8; 32]>>(f: &F) -> String {
+    le_bytes_to_hex(f.to_repr())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Point {
+    x: String,
+    y: String,
+}
+
+impl From<GkG1> for Point {
+    fn from(p: GkG1) -> Self {
+        (&p).into()
+    }
+}
+
+impl From<&GkG1> for Point {
+    fn from(p: &GkG1) -> Self {
+        Point {
+            x: field_to_hex(&p.x),
+            y: field_to_hex(&p.y),
+        }
+    }
+}
+
+impl From<BnG1> for Point {
+    fn from(p: BnG1) -> Self {
+        (&p).into()
+    }
+}
+
+impl From<&BnG1> for Point {
+    fn from(p: &BnG1) -> Self {
+        Point {
+            x: field_to_hex(&p.x),
+            y: field_to_hex(&p.y),
+        }
+    }
+}
+
+// both curves have cofactor 1, so a point on the curve is in the prime order group
+impl TryFrom<&Point> for GkG1 {
+    type Error = Error;
+
+    fn try_from(p: &Point) -> Result<Self, Error> {
+        let x = hex_to_field(&p.x)?;
+        let y = hex_to_field(&p.y)?;
+        Option::from(GkG1::from_xy(x, y))
+            .ok_or_else(|| Error::InvalidEncoding("invalid Grumpkin point".to_string()))
+    }
+}
+
+impl TryFrom<&Point> for BnG1 {
+    type Error = Error;
+
+    fn try_from(p: &Point) -> Result<Self, Error> {
+        let x = hex_to_field(&p.x)?;
+        let y = hex_to_field(&p.y)?;
+        Option::from(BnG1::from_xy(x, y))
+            .ok_or_else(|| Error::InvalidEncoding("invalid Bn256 G1 point".to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Point2 {
+    x: [String; 2],
+    y: [String; 2],
+}
+
+impl From<BnG2> for Point2 {
+    fn from(p: BnG2) -> Self {
+        (&p).into()
+    }
+}
+
+impl From<&BnG2> for Point2 {
+    fn from(p: &BnG2) -> Self {
+        Point2 {
+            x: [field_to_hex(&p.x.c0), field_to_hex(&p.x.c1)],
+            y: [field_to_hex(&p.y.c0), field_to_hex(&p.y.c1)],
+        }
+    }
+}
+
+impl TryFrom<&Point2> for BnG2 {
+    type Error = Error;
+
+    fn try_from(p: &Point2) -> Result<Self, Error> {
+        let x = Fq2::new(hex_to_field::<Fq>(&p.x[0])?, hex_to_field(&p.x[1])?);
+        let y = Fq2::new(hex_to_field::<Fq>(&p.y[0])?, hex_to_field(&p.y[1])?);
+        let p: BnG2 = Option::from(BnG2::from_xy(x, y))
+            .ok_or_else(|| Error::InvalidEncoding("invalid Bn256 G2 point".to_string()))?;
+
+        if !is_in_g2_subgroup(&p) {
+            return Err(Error::InvalidEncoding(
+                "Bn256 G2 point is not in the prime order subgroup".to_string(),
+            ));
+        }
+
+        Ok(p)
+    }
+}
+
+fn points_to_hex<'a, C: 'a>(points: impl IntoIterator<Item = &'a C>) -> Vec<Point>
+where
+    Point: From<&'a C>,
+{
+    points.into_iter().map(Point::from).collect()
+}
+
+fn hex_to_points<C>(points: &[Point]) -> Result<Vec<C>, Error>
+where
+    for<'a> C: TryFrom<&'a Point, Error = Error>,
+{
+    points.iter().map(C::try_from).collect()
+}
+
+fn scalars_to_hex(scalars: &[BnScalar]) -> Vec<String> {
+    scalars.iter().map(field_to_hex).collect()
+}
+
+fn hex_to_scalars(scalars: &[String]) -> Result<Vec<BnScalar>, Error> {
+    scalars.iter().map(|s| hex_to_field(s)).collect()
+}
+
+// the hex encodings of secret keys are wiped when the mirror structs are dropped
+impl<T: Wipe + Serialize> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.expose_secret().serialize(serializer)
+    }
+}
+
+impl<'de, T: Wipe + Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Secret::new)
+    }
+}
+
+// implement Serialize and Deserialize for a library type through its hex encoding
+macro_rules! serde_via_hex {
+    ($ty:ty, $hex:ty) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                <$hex>::from(self).serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let hex = <$hex>::deserialize(deserializer)?;
+                Self::try_from(&hex).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+#[derive(Serialize, Deserialize)]
+struct MemberKeyHex {
+    sk: Secret<String>,
+    pk: Point,
+}
+
+impl From<&MemberKey> for MemberKeyHex {
+    fn from(mk: &MemberKey) -> Self {
+        MemberKeyHex {
+            sk: Secret::new(field_to_hex(mk.secret_key())),
+            pk: (&mk.public_key()).into(),
+        }
+    }
+}
+
+impl TryFrom<&MemberKeyHex> for MemberKey {
+    type Error = Error;
+
+    fn try_from(mk: &MemberKeyHex) -> Result<Self, Error> {
+        let sk = hex_to_field(mk.sk.expose_secret())?;
+        let pk = GkG1::try_from(&mk.pk)?;
+        if (GkG1::generator() * sk).to_affine() != pk {
+            return Err(Error::InvalidEncoding(
+                "public key does not match secret key".to_string(),
+            ));
+        }
+
+        Ok(MemberKey::new(sk, pk))
+    }
+}
+
+serde_via_hex!(MemberKey, MemberKeyHex);
+
+#[derive(Serialize, Deserialize)]
+struct DkgMemberPublicParamsHex {
+    public_shares: Vec<Point>,
+    ciphers: Vec<String>,
+    gr: Point,
+    ga: Point,
+    g2a: Point2,
+}
+
+impl From<&DkgMemberPublicParams> for DkgMemberPublicParamsHex {
+    fn from(pp: &DkgMemberPublicParams) -> Self {
+        DkgMemberPublicParamsHex {
+            public_shares: points_to_hex(&pp.public_shares),
+            ciphers: scalars_to_hex(&pp.ciphers),
+            gr: (&pp.gr).into(),
+            ga: (&pp.ga).into(),
+            g2a: (&pp.g2a).into(),
+        }
+    }
+}
+
+impl TryFrom<&DkgMemberPublicParamsHex> for DkgMemberPublicParams {
+    type Error = Error;
+
+    fn try_from(pp: &DkgMemberPublicParamsHex) -> Result<Self, Error> {
+        Ok(DkgMemberPublicParams {
+            public_shares: hex_to_points(&pp.public_shares)?,
+            ciphers: hex_to_scalars(&pp.ciphers)?,
+            gr: (&pp.gr).try_into()?,
+            ga: (&pp.ga).try_into()?,
+            g2a: (&pp.g2a).try_into()?,
+        })
+    }
+}
+
+serde_via_hex!(DkgMemberPublicParams, DkgMemberPublicParamsHex);
+
+#[derive(Serialize, Deserialize)]
+struct DkgMemberParamsHex {
+    dkg_config: crate::dkg::DkgConfig,
+    coeffs: Secret<Vec<String>>,
+    shares: Secret<Vec<String>>,
+    r: Secret<String>,
+    public_keys: Vec<Point>,
+    public_params: DkgMemberPublicParamsHex,
+}
+
+impl From<&DkgMemberParams> for DkgMemberParamsHex {
+    fn from(mp: &DkgMemberParams) -> Self {
+        DkgMemberParamsHex {
+            dkg_config: mp.dkg_config,
+            coeffs: Secret::new(scalars_to_hex(mp.coeffs.expose_secret())),
+            shares: Secret::new(scalars_to_hex(mp.shares.expose_secret())),
+            r: Secret::new(field_to_hex(mp.r.expose_secret())),
+            public_keys: points_to_hex(&mp.public_keys),
+            public_params: (&mp.public_params).into(),
+        }
+    }
+}
+
+impl TryFrom<&DkgMemberParamsHex> for DkgMemberParams {
+    type Error = Error;
+
+    fn try_from(mp: &DkgMemberParamsHex) -> Result<Self, Error> {
+        Ok(DkgMemberParams {
+            dkg_config: mp.dkg_config,
+            coeffs: Secret::new(hex_to_scalars(mp.coeffs.expose_secret())?),
+            shares: Secret::new(hex_to_scalars(mp.shares.expose_secret())?),
+            r: Secret::new(hex_to_field(mp.r.expose_secret())?),
+            public_keys: hex_to_points(&mp.public_keys)?,
+            public_params: (&mp.public_params).try_into()?,
+        })
+    }
+}
+
+serde_via_hex!(DkgMemberParams, DkgMemberParamsHex);
+
+#[derive(Serialize, Deserialize)]
+struct DkgGlobalPubParamsHex {
+    ga: Point,
+    g2a: Point2,
+    verify_keys: Vec<Point>,
+}
+
+impl From<&DkgGlobalPubParams> for DkgGlobalPubParamsHex {
+    fn from(gpp: &DkgGlobalPubParams) -> Self {
+        DkgGlobalPubParamsHex {
+            ga: (&gpp.ga).into(),
+            g2a: (&gpp.g2a).into(),
+            verify_keys: points_to_hex(&gpp.verify_keys),
+        }
+    }
+}
+
+impl TryFrom<&DkgGlobalPubParamsHex> for DkgGlobalPubParams {
+    type Error = Error;
+
+    fn try_from(gpp: &DkgGlobalPubParamsHex) -> Result<Self, Error> {
+        Ok(DkgGlobalPubParams {
+            ga: (&gpp.ga).try_into()?,
+            g2a: (&gpp.g2a).try_into()?,
+            verify_keys: hex_to_points(&gpp.verify_keys)?,
+        })
+    }
+}
+
+serde_via_hex!(DkgGlobalPubParams, DkgGlobalPubParamsHex);
+
+#[derive(Serialize, Deserialize)]
+struct DkgShareKeyHex {
+    index: usize,
+    sk: Secret<String>,
+    vk: Point,
+    // share keys written before refreshes were introduced have no epoch
+    #[serde(default)]
+    epoch: u64,
+}
+
+impl From<&DkgShareKey> for DkgShareKeyHex {
+    fn from(share: &DkgShareKey) -> Self {
+        DkgShareKeyHex {
+            index: share.index(),
+            sk: Secret::new(field_to_hex(share.secret_key())),
+            vk: (&share.verify_key()).into(),
+            epoch: share.epoch(),
+        }
+    }
+}
+
+impl TryFrom<&DkgShareKeyHex> for DkgShareKey {
+    type Error = Error;
+
+    fn try_from(share: &DkgShareKeyHex) -> Result<Self, Error> {
+        let sk = hex_to_field(share.sk.expose_secret())?;
+        let vk = BnG1::try_from(&share.vk)?;
+        if (BnG1::generator() * sk).to_affine() != vk {
+            return Err(Error::InvalidEncoding(
+                "verification key does not match secret key".to_string(),
+            ));
+        }
+
+        Ok(DkgShareKey::new_with_epoch(
+            share.index,
+            sk,
+            vk,
+            share.epoch,
+        ))
+    }
+}
+
+serde_via_hex!(DkgShareKey, DkgShareKeyHex);
+
+#[derive(Serialize, Deserialize)]
+struct PartialEvalProofHex {
+    z: String,
+    c: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PartialEvalHex {
+    index: usize,
+    value: Point,
+    proof: PartialEvalProofHex,
+}
+
+impl From<&PartialEval> for PartialEvalHex {
+    fn from(sigma: &PartialEval) -> Self {
+        PartialEvalHex {
+            index: sigma.index,
+            value: (&sigma.value).into(),
+            proof: PartialEvalProofHex {
+                z: field_to_hex(&sigma.proof.z),
+                c: field_to_hex(&sigma.proof.c),
+            },
+        }
+    }
+}
+
+impl TryFrom<&PartialEvalHex> for PartialEval {
+    type Error = Error;
+
+    fn try_from(sigma: &PartialEvalHex) -> Result<Self, Error> {
+        Ok(PartialEval {
+            index: sigma.index,
+            value: (&sigma.value).try_into()?,
+            proof: PartialEvalProof {
+                z: hex_to_field(&sigma.proof.z)?,
+                c: hex_to_field(&sigma.proof.c)?,
+            },
+        })
+    }
+}
+
+serde_via_hex!(PartialEval, PartialEvalHex);
+
+#[derive(Serialize, Deserialize)]
+struct BatchableEvalProofHex {
+    cap_r_1: Point,
+    cap_r_2: Point,
+    z: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BatchablePartialEvalHex {
+    index: usize,
+    value: Point,
+    proof: BatchableEvalProofHex,
+}
+
+impl From<&BatchablePartialEval> for BatchablePartialEvalHex {
+    fn from(sigma: &BatchablePartialEval) -> Self {
+        BatchablePartialEvalHex {
+            index: sigma.index,
+            value: (&sigma.value).into(),
+            proof: BatchableEvalProofHex {
+                cap_r_1: (&sigma.proof.cap_r_1).into(),
+                cap_r_2: (&sigma.proof.cap_r_2).into(),
+                z: field_to_hex(&sigma.proof.z),
+            },
+        }
+    }
+}
+
+impl TryFrom<&BatchablePartialEvalHex> for BatchablePartialEval {
+    type Error = Error;
+
+    fn try_from(sigma: &BatchablePartialEvalHex) -> Result<Self, Error> {
+        Ok(BatchablePartialEval {
+            index: sigma.index,
+            value: (&sigma.value).try_into()?,
+            proof: BatchableEvalProof {
+                cap_r_1: (&sigma.proof.cap_r_1).try_into()?,
+                cap_r_2: (&sigma.proof.cap_r_2).try_into()?,
+                z: hex_to_field(&sigma.proof.z)?,
+            },
+        })
+    }
+}
+
+serde_via_hex!(BatchablePartialEval, BatchablePartialEvalHex);
+
+#[derive(Serialize, Deserialize)]
+struct WeightedConfigFields {
+    threshold: usize,
+    weights: Vec<usize>,
+}
+
+impl From<&WeightedConfig> for WeightedConfigFields {
+    fn from(config: &WeightedConfig) -> Self {
+        WeightedConfigFields {
+            threshold: config.threshold(),
+            weights: config.weights().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<&WeightedConfigFields> for WeightedConfig {
+    type Error = Error;
+
+    fn try_from(config: &WeightedConfigFields) -> Result<Self, Error> {
+        WeightedConfig::new(config.threshold, config.weights.clone())
+    }
+}
+
+serde_via_hex!(WeightedConfig, WeightedConfigFields);
+
+#[derive(Serialize, Deserialize)]
+struct PseudoRandomHex {
+    proof: Point,
+    value: Vec<u8>,
+}
+
+impl From<&PseudoRandom> for PseudoRandomHex {
+    fn from(pseudo: &PseudoRandom) -> Self {
+        PseudoRandomHex {
+            proof: (&pseudo.proof).into(),
+            value: pseudo.value.clone(),
+        }
+    }
+}
+
+impl TryFrom<&PseudoRandomHex> for PseudoRandom {
+    type Error = Error;
+
+    fn try_from(pseudo: &PseudoRandomHex) -> Result<Self, Error> {
+        Ok(PseudoRandom::new(
+            (&pseudo.proof).try_into()?,
+            pseudo.value.clone(),
+        ))
+    }
+}
+
+serde_via_hex!(PseudoRandom, PseudoRandomHex);
+
+impl From<&Signature> for Point {
+    fn from(signature: &Signature) -> Self {
+        (&signature.0).into()
+    }
+}
+
+impl TryFrom<&Point> for Signature {
+    type Error = Error;
+
+    fn try_from(point: &Point) -> Result<Self, Error> {
+        Ok(Signature(point.try_into()?))
+    }
+}
+
+serde_via_hex!(Signature, Point);
+
+#[derive(Serialize, Deserialize)]
+struct ComplaintHex {
+    accuser: usize,
+    dealer: usize,
+    pkr: Point,
+    z: String,
+    c: String,
+}
+
+impl From<&Complaint> for ComplaintHex {
+    fn from(complaint: &Complaint) -> Self {
+        ComplaintHex {
+            accuser: complaint.accuser,
+            dealer: complaint.dealer,
+            pkr: (&complaint.pkr).into(),
+            z: field_to_hex(&complaint.proof.z),
+            c: field_to_hex(&complaint.proof.c),
+        }
+    }
+}
+
+impl TryFrom<&ComplaintHex> for Complaint {
+    type Error = Error;
+
+    fn try_from(complaint: &ComplaintHex) -> Result<Self, Error> {
+        Ok(Complaint {
+            accuser: complaint.accuser,
+            dealer: complaint.dealer,
+            pkr: (&complaint.pkr).try_into()?,
+            proof: DleqProof {
+                z: hex_to_field(&complaint.z)?,
+                c: hex_to_field(&complaint.c)?,
+            },
+        })
+    }
+}
+
+serde_via_hex!(Complaint, ComplaintHex);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::{DkgConfig, EvalDomain};
+    use crate::dkg_global_public_params;
+    use halo2wrong::halo2::arithmetic::Field;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_hex() {
+        let mut rng = OsRng;
+        let x = BnScalar::random(&mut rng);
+        let bytes = x.to_bytes();
+        let xs = le_bytes_to_hex(bytes);
+        let z = hex_to_le_bytes(&xs).unwrap();
+        assert_eq!(bytes, z);
+
+        assert!(hex_to_le_bytes("0xzz").is_err());
+        assert!(hex_to_le_bytes(&format!("0x{}", "ff".repeat(33))).is_err());
+        // the modulus is not a canonical scalar
+        assert!(hex_to_field::<BnScalar>(BnScalar::MODULUS).is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let members: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| MemberKey::random(&mut rng))
+            .collect();
+        let pks: Vec<_> = members.iter().map(|m| m.public_key()).collect();
+        let dkgs: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| DkgMemberParams::new(dkg_config, pks.clone(), &mut rng).unwrap())
+            .collect();
+        let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
+        let gpp = dkg_global_public_params(&dkg_config, &dkgs_pub).unwrap();
+        let share = members[0].dkg_share_key(&dkg_config, 1, &dkgs_pub).unwrap();
+        let sigma = share.evaluate(&EvalDomain::default(), b"serde", &mut rng);
+
+        let member: MemberKey =
+            serde_json::from_str(&serde_json::to_string(&members[0]).unwrap()).unwrap();
+        assert_eq!(member.secret_key(), members[0].secret_key());
+
+        let dkg: DkgMemberParams =
+            serde_json::from_str(&serde_json::to_string(&dkgs[0]).unwrap()).unwrap();
+        assert_eq!(dkg.coeffs.expose_secret(), dkgs[0].coeffs.expose_secret());
+        assert_eq!(dkg.public_params.g2a, dkgs[0].public_params.g2a);
+
+        let decoded: DkgGlobalPubParams =
+            serde_json::from_str(&serde_json::to_string(&gpp).unwrap()).unwrap();
+        assert_eq!(decoded.verify_keys, gpp.verify_keys);
+
+        let decoded: DkgShareKey =
+            serde_json::from_str(&serde_json::to_string(&share).unwrap()).unwrap();
+        assert_eq!(decoded.secret_key(), share.secret_key());
+        assert_eq!(decoded.epoch(), share.epoch());
+
+        let decoded: PartialEval =
+            serde_json::from_str(&serde_json::to_string(&sigma).unwrap()).unwrap();
+        decoded
+            .verify(
+                &dkg_config,
+                &EvalDomain::default(),
+                b"serde",
+                &gpp.verify_keys[0],
+            )
+            .unwrap();
+
+        let batchable = share.evaluate_batchable(&EvalDomain::default(), b"serde", &mut rng);
+        let decoded: BatchablePartialEval =
+            serde_json::from_str(&serde_json::to_string(&batchable).unwrap()).unwrap();
+        crate::verify_batchable_evaluations(
+            &dkg_config,
+            &EvalDomain::default(),
+            b"serde",
+            &[decoded],
+            &gpp.verify_keys,
+            &mut rng,
+        )
+        .unwrap();
+
+        // a point off the curve is rejected
+        let mut bad = Point::from(&gpp.ga);
+        bad.y = bad.x.clone();
+        assert!(BnG1::try_from(&bad).is_err());
+
+        // a point on the twist which is not in the subgroup of order r is rejected
+        // b = 3 / (9 + u) for the twist
+        let b = Fq2::new(Fq::from(3), Fq::ZERO) * Fq2::new(Fq::from(9), Fq::ONE).invert().unwrap();
+        let mut x = Fq2::ONE;
+        let p = loop {
+            let y2 = x.square() * x + b;
+            if let Some(y) = Option::<Fq2>::from(y2.sqrt()) {
+                let p = BnG2 { x, y };
+                if !is_in_g2_subgroup(&p) {
+                    break p;
+                }
+            }
+            x += Fq2::ONE;
+        };
+        assert!(BnG2::try_from(&Point2::from(&p)).is_err());
+        assert!(BnG2::try_from(&Point2::from(&BnG2::generator())).is_ok());
+    }
+}
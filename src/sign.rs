@@ -0,0 +1,239 @@
+// threshold BLS signatures with the dkg shares; a signature share is a partial evaluation
+// on the message under SIGN_PREFIX, so it carries the same proof against the member's
+// verification key, and the interpolated signature H(m)^a verifies against gpk = g2^a
+use crate::dkg::{
+    check_indices, lagrange_coefficients, verify_partial_evaluations, DkgConfig, DkgShareKey,
+    EvalDomain, HashToCurve, PartialEval,
+};
+use crate::error::Error;
+use halo2_ecc::halo2::halo2curves::bn256::G2Prepared;
+use halo2_maingate::halo2::halo2curves::bn256::multi_miller_loop;
+use halo2wrong::curves::bn256::{G1Affine as BnG1, G2Affine as BnG2, G1 as BnG1Curve};
+use halo2wrong::curves::group::prime::PrimeCurveAffine;
+use halo2wrong::curves::group::{Curve, Group};
+use halo2wrong::curves::pairing::MillerLoopResult;
+use halo2wrong::halo2::arithmetic::best_multiexp;
+use rand_core::RngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// separates signatures from pseudorandom evaluations on the same input
+pub const SIGN_PREFIX: &str = "zkRand threshold BLS signature 2024";
+
+fn sign_domain() -> EvalDomain {
+    EvalDomain::from_prefix(SIGN_PREFIX)
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct SignatureShare(pub PartialEval);
+
+impl SignatureShare {
+    pub fn index(&self) -> usize {
+        self.0.index
+    }
+
+    pub fn verify(&self, dkg_config: &DkgConfig, message: &[u8], vk: &BnG1) -> Result<(), Error> {
+        self.0.verify(dkg_config, &sign_domain(), message, vk)
+    }
+}
+
+impl DkgShareKey {
+    pub fn sign(&self, message: &[u8], rng: impl RngCore) -> SignatureShare {
+        SignatureShare(self.evaluate(&sign_domain(), message, rng))
+    }
+
+    pub fn sign_deterministic(&self, message: &[u8]) -> SignatureShare {
+        SignatureShare(self.evaluate_deterministic(&sign_domain(), message))
+    }
+}
+
+// verify signature shares on the same message against the verification keys of all members;
+// returns the indices of the invalid shares as an error
+pub fn verify_signature_shares(
+    dkg_config: &DkgConfig,
+    message: &[u8],
+    shares: &[SignatureShare],
+    vks: &[BnG1],
+) -> Result<(), Error> {
+    let sigmas: Vec<_> = shares.iter().map(|share| share.0.clone()).collect();
+    verify_partial_evaluations(dkg_config, &sign_domain(), message, &sigmas, vks)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature(pub BnG1);
+
+impl Signature {
+    pub fn verify(&self, message: &[u8], gpk: &BnG2) -> Result<(), Error> {
+        verify_aggregate_signature(&[message], self, gpk)
+    }
+}
+
+// interpolate the signature from threshold shares with distinct indices in any order;
+// the shares are not verified, see verify_signature_shares
+pub fn aggregate_signature_shares(
+    dkg_config: &DkgConfig,
+    shares: &[SignatureShare],
+) -> Result<Signature, Error> {
+    if shares.len() < dkg_config.threshold() {
+        return Err(Error::NotEnoughEvaluations {
+            valid: shares.len(),
+            required: dkg_config.threshold(),
+        });
+    }
+
+    let mut sorted: Vec<_> = shares.iter().collect();
+    sorted.sort_by_key(|share| share.index());
+    let indices: Vec<_> = sorted.iter().map(|share| share.index()).collect();
+    check_indices(dkg_config.number_of_members(), &indices)?;
+
+    let indices = &indices[0..dkg_config.threshold()];
+    let lambdas = lagrange_coefficients(indices);
+    let values: Vec<_> = sorted
+        .iter()
+        .take(dkg_config.threshold())
+        .map(|share| share.0.value)
+        .collect();
+
+    Ok(Signature(best_multiexp(&lambdas, &values).to_affine()))
+}
+
+// aggregate signatures of the committee on many messages into a single point
+pub fn aggregate_signatures(signatures: &[Signature]) -> Signature {
+    let sum = signatures
+        .iter()
+        .fold(BnG1Curve::identity(), |acc, signature| acc + signature.0);
+    Signature(sum.to_affine())
+}
+
+// e(signature, g2) = e(sum H(m_i), gpk), since every message is signed with the same key
+pub fn verify_aggregate_signature(
+    messages: &[&[u8]],
+    signature: &Signature,
+    gpk: &BnG2,
+) -> Result<(), Error> {
+    if messages.is_empty() {
+        return Err(Error::VerifyFailed);
+    }
+
+    let domain = sign_domain();
+    let h = messages
+        .iter()
+        .fold(BnG1Curve::identity(), |acc, message| {
+            acc + domain.hash_to_curve(message)
+        })
+        .to_affine();
+
+    let gpk_prepared = G2Prepared::from_affine(*gpk);
+    let g2_prepared = G2Prepared::from_affine(BnG2::generator());
+
+    let t = multi_miller_loop(&[(&-h, &gpk_prepared), (&signature.0, &g2_prepared)])
+        .final_exponentiation();
+
+    if !bool::from(t.is_identity()) {
+        return Err(Error::VerifyFailed);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::{shares, PseudoRandom};
+    use halo2wrong::curves::bn256::Fr as BnScalar;
+    use halo2wrong::halo2::arithmetic::Field;
+    use rand_core::OsRng;
+
+    fn mock_shares(dkg_config: &DkgConfig, mut rng: impl RngCore) -> (Vec<DkgShareKey>, BnG2) {
+        let coeffs: Vec<_> = (0..dkg_config.threshold())
+            .map(|_| BnScalar::random(&mut rng))
+            .collect();
+        let share_keys = shares(dkg_config.number_of_members(), &coeffs)
+            .into_iter()
+            .enumerate()
+            .map(|(i, sk)| DkgShareKey::new(i + 1, sk, (BnG1::generator() * sk).to_affine()))
+            .collect();
+        let gpk = (BnG2::generator() * coeffs[0]).to_affine();
+
+        (share_keys, gpk)
+    }
+
+    #[test]
+    fn test_threshold_signature() {
+        let mut rng = OsRng;
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let (share_keys, gpk) = mock_shares(&dkg_config, &mut rng);
+        let vks: Vec<_> = share_keys.iter().map(|s| s.verify_key()).collect();
+
+        let message = b"bridge message 1";
+        let shares: Vec<_> = share_keys
+            .iter()
+            .map(|s| s.sign(message, &mut rng))
+            .collect();
+        for share in shares.iter() {
+            share
+                .verify(&dkg_config, message, &vks[share.index() - 1])
+                .unwrap();
+        }
+        verify_signature_shares(&dkg_config, message, &shares, &vks).unwrap();
+        assert!(shares[0].verify(&dkg_config, b"other", &vks[0]).is_err());
+
+        // any threshold subset in any order gives the same signature
+        let signature = aggregate_signature_shares(&dkg_config, &shares[0..3]).unwrap();
+        let reordered = [shares[4].clone(), shares[1].clone(), shares[3].clone()];
+        assert_eq!(
+            aggregate_signature_shares(&dkg_config, &reordered).unwrap(),
+            signature
+        );
+        assert_eq!(
+            aggregate_signature_shares(
+                &dkg_config,
+                &[
+                    share_keys[2].sign_deterministic(message),
+                    shares[3].clone(),
+                    shares[0].clone()
+                ]
+            )
+            .unwrap(),
+            signature
+        );
+        signature.verify(message, &gpk).unwrap();
+        assert!(signature.verify(b"other", &gpk).is_err());
+
+        assert!(matches!(
+            aggregate_signature_shares(&dkg_config, &shares[0..2]),
+            Err(Error::NotEnoughEvaluations { .. })
+        ));
+        let duplicated = [shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert!(aggregate_signature_shares(&dkg_config, &duplicated).is_err());
+
+        // the signature is not a pseudorandom evaluation on the same input
+        let pseudo = PseudoRandom::new(signature.0, vec![]);
+        assert!(pseudo
+            .verify(&EvalDomain::default(), message, &gpk)
+            .is_err());
+
+        // aggregation across messages
+        let messages: Vec<&[u8]> = vec![
+            &b"bridge message 1"[..],
+            &b"bridge message 2"[..],
+            &b"bridge message 3"[..],
+        ];
+        let signatures: Vec<_> = messages
+            .iter()
+            .map(|m| {
+                let shares: Vec<_> = share_keys[1..4]
+                    .iter()
+                    .map(|s| s.sign(m, &mut rng))
+                    .collect();
+                aggregate_signature_shares(&dkg_config, &shares).unwrap()
+            })
+            .collect();
+        let aggregated = aggregate_signatures(&signatures);
+        verify_aggregate_signature(&messages, &aggregated, &gpk).unwrap();
+        assert!(verify_aggregate_signature(&messages[0..2], &aggregated, &gpk).is_err());
+        assert!(verify_aggregate_signature(&[], &aggregated, &gpk).is_err());
+    }
+}
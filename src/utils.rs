@@ -1,14 +1,16 @@
 use crate::dkg::DkgConfig;
+use crate::error::Error;
 use crate::hash_to_curve::svdw_hash_to_curve;
 use crate::{
-    DkgCircuit, BIT_LEN_LIMB, COORD_LEN, NUMBER_OF_LIMBS, NUMBER_OF_LOOKUP_LIMBS, POINT_LEN,
-    WRAP_LEN,
+    CircuitKind, DkgCircuit, BIT_LEN_LIMB, COORD_LEN, NUMBER_OF_LIMBS, NUMBER_OF_LOOKUP_LIMBS,
+    POINT_LEN, WRAP_LEN,
 };
 use anyhow::Result;
 use ark_std::{end_timer, start_timer};
 use halo2_ecc::integer::rns::Rns;
 use halo2_ecc::Point;
-use halo2wrong::curves::ff::PrimeField;
+use halo2wrong::curves::ff::{FromUniformBytes, PrimeField};
+use halo2wrong::curves::group::Curve;
 use halo2wrong::curves::{
     bn256::{self, Bn256},
     grumpkin, CurveAffine, CurveExt,
@@ -82,26 +84,46 @@ pub fn point_to_public<W: PrimeField, N: PrimeField>(
 
 pub fn public_to_point<W: PrimeField, N: PrimeField<Repr = W::Repr>, C: CurveAffine<Base = W>>(
     public: &[N],
-) -> C {
-    assert_eq!(public.len(), POINT_LEN);
+) -> Result<C, Error> {
+    check_length(POINT_LEN, public.len())?;
 
     let two = W::from(2);
     let base = two.pow([(BIT_LEN_LIMB * WRAP_LEN) as u64]);
 
-    let mut x = W::from_repr(public[COORD_LEN - 1].to_repr()).unwrap();
+    let mut x = limb_to_base::<W, N>(&public[COORD_LEN - 1])?;
     for i in (0..COORD_LEN - 1).rev() {
-        let v = W::from_repr(public[i].to_repr()).unwrap();
+        let v = limb_to_base::<W, N>(&public[i])?;
         x = x * base + v;
     }
 
-    let mut y = W::from_repr(public[POINT_LEN - 1].to_repr()).unwrap();
+    let mut y = limb_to_base::<W, N>(&public[POINT_LEN - 1])?;
     for i in (COORD_LEN..POINT_LEN - 1).rev() {
-        let v = W::from_repr(public[i].to_repr()).unwrap();
+        let v = limb_to_base::<W, N>(&public[i])?;
         y = y * base + v;
     }
 
-    let point = C::from_xy(x, y).unwrap();
-    point
+    Option::from(C::from_xy(x, y)).ok_or(Error::InvalidPoint)
+}
+
+fn limb_to_base<W: PrimeField, N: PrimeField<Repr = W::Repr>>(limb: &N) -> Result<W, Error> {
+    Option::from(W::from_repr(limb.to_repr()))
+        .ok_or_else(|| Error::MalformedInstance("limb is not a base field element".to_string()))
+}
+
+// the Bn256 scalar field is smaller than the Grumpkin scalar field, so the canonical bytes of
+// a Bn256 scalar reduce to the same integer as a Grumpkin scalar
+pub(crate) fn bn_to_grumpkin_scalar(s: &bn256::Fr) -> grumpkin::Fr {
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(s.to_repr().as_ref());
+    grumpkin::Fr::from_uniform_bytes(&bytes)
+}
+
+pub(crate) fn check_length(expected: usize, actual: usize) -> Result<(), Error> {
+    if expected != actual {
+        return Err(Error::LengthMismatch { expected, actual });
+    }
+
+    Ok(())
 }
 
 #[cfg(feature = "g2chip")]
@@ -142,8 +164,8 @@ pub fn public_to_point2<
     C: CurveAffine + SplitBase<C::Base, W>,
 >(
     public: &[N],
-) -> C {
-    assert_eq!(public.len(), POINT_LEN * 2);
+) -> Result<C, Error> {
+    check_length(POINT_LEN * 2, public.len())?;
 
     let two = W::from(2);
     let base = two.pow([(BIT_LEN_LIMB * WRAP_LEN) as u64]);
@@ -153,9 +175,9 @@ pub fn public_to_point2<
         let begin = i * COORD_LEN;
         let end = begin + COORD_LEN - 1;
 
-        let mut c = W::from_repr(public[end].to_repr()).unwrap();
+        let mut c = limb_to_base::<W, N>(&public[end])?;
         for i in (begin..end).rev() {
-            let v = W::from_repr(public[i].to_repr()).unwrap();
+            let v = limb_to_base::<W, N>(&public[i])?;
             c = c * base + v;
         }
 
@@ -165,7 +187,17 @@ pub fn public_to_point2<
     let x = <C as SplitBase<C::Base, W>>::from(coords[0], coords[1]);
     let y = <C as SplitBase<C::Base, W>>::from(coords[2], coords[3]);
 
-    C::from_xy(x, y).unwrap()
+    Option::from(C::from_xy(x, y)).ok_or(Error::InvalidPoint)
+}
+
+// grumpkin points are exposed in the instance by their native coordinates
+pub(crate) fn public_to_grumpkin(x: bn256::Fr, y: bn256::Fr) -> Result<grumpkin::G1Affine, Error> {
+    Option::from(grumpkin::G1Affine::from_xy(x, y)).ok_or(Error::InvalidPoint)
+}
+
+// Bn256 G2 has a large cofactor; p is in the subgroup of order r iff (r - 1) * p == -p
+pub(crate) fn is_in_g2_subgroup(p: &bn256::G2Affine) -> bool {
+    (p * (-bn256::Fr::one())).to_affine() == -p
 }
 
 pub fn hash_to_curve_bn<'a>(domain_prefix: &'a str) -> Box<dyn Fn(&[u8]) -> bn256::G1 + 'a> {
@@ -250,22 +282,53 @@ pub fn load_params(
     Ok(p)
 }
 
+// proving and verifying keys are stored in params_dir as "{pk|vk}-[kind-][g2-]{t}-{n}-{degree}";
+// the keys of the NIDKG circuit keep the names without kind
+fn key_path(
+    key: &str,
+    dkg_config: &DkgConfig,
+    kind: CircuitKind,
+    params_dir: &str,
+    degree: usize,
+) -> String {
+    let threshold = dkg_config.threshold();
+    let number_of_members = dkg_config.number_of_members();
+    let kind = match kind {
+        CircuitKind::Dkg => "",
+        CircuitKind::Compressed => "compressed-",
+        CircuitKind::Refresh => "refresh-",
+    };
+    let g2 = if cfg!(feature = "g2chip") { "g2-" } else { "" };
+
+    format!("{params_dir}/{key}-{kind}{g2}{threshold}-{number_of_members}-{degree}")
+}
+
 pub fn load_pk(
     dkg_config: &DkgConfig,
     params_dir: &str,
     degree: usize,
     serde_format: SerdeFormat,
+) -> Result<ProvingKey<bn256::G1Affine>> {
+    load_pk_of(
+        dkg_config,
+        CircuitKind::Dkg,
+        params_dir,
+        degree,
+        serde_format,
+    )
+}
+
+fn load_pk_of(
+    dkg_config: &DkgConfig,
+    kind: CircuitKind,
+    params_dir: &str,
+    degree: usize,
+    serde_format: SerdeFormat,
 ) -> Result<ProvingKey<bn256::G1Affine>> {
     log::info!("start loading pk with degree {}", degree);
     let pk_path = if metadata(params_dir)?.is_dir() {
-        let threshold = dkg_config.threshold();
-        let number_of_members = dkg_config.number_of_members();
         // auto load
-        if cfg!(feature = "g2chip") {
-            format!("{params_dir}/pk-g2-{threshold}-{number_of_members}-{degree}")
-        } else {
-            format!("{params_dir}/pk-{threshold}-{number_of_members}-{degree}")
-        }
+        key_path("pk", dkg_config, kind, params_dir, degree)
     } else {
         params_dir.to_string()
     };
@@ -281,17 +344,27 @@ pub fn load_vk(
     params_dir: &str,
     degree: usize,
     serde_format: SerdeFormat,
+) -> Result<VerifyingKey<bn256::G1Affine>> {
+    load_vk_of(
+        dkg_config,
+        CircuitKind::Dkg,
+        params_dir,
+        degree,
+        serde_format,
+    )
+}
+
+fn load_vk_of(
+    dkg_config: &DkgConfig,
+    kind: CircuitKind,
+    params_dir: &str,
+    degree: usize,
+    serde_format: SerdeFormat,
 ) -> Result<VerifyingKey<bn256::G1Affine>> {
     log::info!("start loading vk with degree {}", degree);
     let vk_path = if metadata(params_dir)?.is_dir() {
-        let threshold = dkg_config.threshold();
-        let number_of_members = dkg_config.number_of_members();
         // auto load
-        if cfg!(feature = "g2chip") {
-            format!("{params_dir}/vk-g2-{threshold}-{number_of_members}-{degree}")
-        } else {
-            format!("{params_dir}/vk-{threshold}-{number_of_members}-{degree}")
-        }
+        key_path("vk", dkg_config, kind, params_dir, degree)
     } else {
         params_dir.to_string()
     };
@@ -307,6 +380,17 @@ pub fn load_or_create_vk(
     params_dir: &str,
     params: &ParamsKZG<Bn256>,
     degree: usize,
+) -> Result<VerifyingKey<bn256::G1Affine>> {
+    load_or_create_vk_of(dkg_config, CircuitKind::Dkg, params_dir, params, degree)
+}
+
+// verifying key of the circuit of the given kind, generated from DkgCircuit::dummy_of
+pub fn load_or_create_vk_of(
+    dkg_config: DkgConfig,
+    kind: CircuitKind,
+    params_dir: &str,
+    params: &ParamsKZG<Bn256>,
+    degree: usize,
 ) -> Result<VerifyingKey<bn256::G1Affine>> {
     if !metadata(params_dir)?.is_dir() {
         return Err(anyhow::format_err!(
@@ -315,26 +399,17 @@ pub fn load_or_create_vk(
         ));
     }
 
-    if let Ok(vk) = load_vk(&dkg_config, params_dir, degree, DEFAULT_SERDE_FORMAT) {
+    if let Ok(vk) = load_vk_of(&dkg_config, kind, params_dir, degree, DEFAULT_SERDE_FORMAT) {
         return Ok(vk);
     }
 
     let start = start_timer!(|| "failed to load vk; generate verifying key vk");
-    let circuit_dummy = DkgCircuit::dummy(dkg_config);
+    let circuit_dummy = DkgCircuit::dummy_of(dkg_config, kind);
     let vk = keygen_vk(params, &circuit_dummy).expect("keygen_vk should not fail");
     end_timer!(start);
 
     let start = start_timer!(|| "store vk to file");
-    let vk_path = {
-        let threshold = dkg_config.threshold();
-        let number_of_members = dkg_config.number_of_members();
-        // auto load
-        if cfg!(feature = "g2chip") {
-            format!("{params_dir}/vk-g2-{threshold}-{number_of_members}-{degree}")
-        } else {
-            format!("{params_dir}/vk-{threshold}-{number_of_members}-{degree}")
-        }
-    };
+    let vk_path = key_path("vk", &dkg_config, kind, params_dir, degree);
     let mut f_vk = File::create(vk_path)?;
     vk.write(&mut f_vk, DEFAULT_SERDE_FORMAT)?;
     end_timer!(start);
@@ -347,6 +422,17 @@ pub fn load_or_create_pk(
     params_dir: &str,
     params: &ParamsKZG<Bn256>,
     degree: usize,
+) -> Result<ProvingKey<bn256::G1Affine>> {
+    load_or_create_pk_of(dkg_config, CircuitKind::Dkg, params_dir, params, degree)
+}
+
+// proving key of the circuit of the given kind, generated from DkgCircuit::dummy_of
+pub fn load_or_create_pk_of(
+    dkg_config: DkgConfig,
+    kind: CircuitKind,
+    params_dir: &str,
+    params: &ParamsKZG<Bn256>,
+    degree: usize,
 ) -> Result<ProvingKey<bn256::G1Affine>> {
     if !metadata(params_dir)?.is_dir() {
         return Err(anyhow::format_err!(
@@ -355,29 +441,20 @@ pub fn load_or_create_pk(
         ));
     }
 
-    if let Ok(pk) = load_pk(&dkg_config, params_dir, degree, DEFAULT_SERDE_FORMAT) {
+    if let Ok(pk) = load_pk_of(&dkg_config, kind, params_dir, degree, DEFAULT_SERDE_FORMAT) {
         return Ok(pk);
     }
 
     log::info!("failed to load pk; generate and store vk and pk");
-    let vk = load_or_create_vk(dkg_config, params_dir, params, degree)?;
-    let circuit_dummy = DkgCircuit::dummy(dkg_config);
+    let vk = load_or_create_vk_of(dkg_config, kind, params_dir, params, degree)?;
+    let circuit_dummy = DkgCircuit::dummy_of(dkg_config, kind);
 
     let start = start_timer!(|| "generate proving key pk");
     let pk = keygen_pk(params, vk, &circuit_dummy).expect("keygen_pk should not fail");
     end_timer!(start);
 
     let start = start_timer!(|| "store pk to file");
-    let pk_path = {
-        let threshold = dkg_config.threshold();
-        let number_of_members = dkg_config.number_of_members();
-        // auto load
-        if cfg!(feature = "g2chip") {
-            format!("{params_dir}/pk-g2-{threshold}-{number_of_members}-{degree}")
-        } else {
-            format!("{params_dir}/pk-{threshold}-{number_of_members}-{degree}")
-        }
-    };
+    let pk_path = key_path("pk", &dkg_config, kind, params_dir, degree);
     let mut f_pk = File::create(pk_path)?;
     pk.write(&mut f_pk, DEFAULT_SERDE_FORMAT)?;
     end_timer!(start);
@@ -432,14 +509,14 @@ mod tests {
 
         let g = BnG1::random(&mut rng);
         let public = point_to_public(Rc::clone(&rns_base), g);
-        let point = public_to_point::<Fq, Fr, BnG1>(&public);
+        let point = public_to_point::<Fq, Fr, BnG1>(&public).unwrap();
         assert_eq!(g, point);
 
         #[cfg(feature = "g2chip")]
         {
             let g2 = BnG2::random(&mut rng);
             let public2 = point2_to_public(Rc::clone(&rns_base), g2);
-            let point2 = public_to_point2::<Fq, Fr, BnG2>(&public2);
+            let point2 = public_to_point2::<Fq, Fr, BnG2>(&public2).unwrap();
             assert_eq!(g2, point2);
         }
     }
@@ -0,0 +1,282 @@
+// weighted threshold dkg: member i holds w_i shares at consecutive evaluation points and the
+// threshold is a total weight. The dkg runs unchanged over the W = w_1 + ... + w_n shares, so
+// shares() and the circuit see W parties. The k-th share of member i is encrypted to the derived
+// key k * pk_i: every share gets its own encryption key, and the member decrypts all of them
+// with the single Grumpkin key sk_i
+use crate::dkg::{combine_partial_evaluations, DkgConfig, PartialEval, PseudoRandom};
+use crate::error::Error;
+use crate::utils::check_length;
+use crate::{DkgMemberParams, DkgMemberPublicParams, DkgShareKey, MemberKey};
+use halo2wrong::curves::group::Curve;
+use halo2wrong::curves::grumpkin::{Fr as GkScalar, G1Affine as GkG1};
+use rand_core::RngCore;
+use std::ops::RangeInclusive;
+
+// the dkg runs over total_weight() shares, so the size of the dealings, the circuit and the
+// verification keys grows with the total weight rather than with the number of members.
+// Deserialisation goes through new() in serialise.rs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedConfig {
+    threshold: usize,
+    weights: Vec<usize>,
+}
+
+impl WeightedConfig {
+    pub fn new(threshold: usize, weights: Vec<usize>) -> Result<Self, Error> {
+        let total = weights
+            .iter()
+            .try_fold(0usize, |acc, w| acc.checked_add(*w));
+        match total {
+            Some(total) if !weights.contains(&0) && threshold > 0 && threshold <= total => {}
+            _ => return Err(Error::InvalidWeights { threshold, weights }),
+        }
+
+        Ok(WeightedConfig { threshold, weights })
+    }
+
+    // the minimum total weight of shares to evaluate
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn number_of_members(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn weights(&self) -> &[usize] {
+        &self.weights
+    }
+
+    pub fn total_weight(&self) -> usize {
+        self.weights.iter().sum()
+    }
+
+    // the dkg configuration over all the shares
+    pub fn dkg_config(&self) -> Result<DkgConfig, Error> {
+        DkgConfig::new(self.threshold, self.total_weight())
+    }
+
+    // indices of the shares of member i, where members are indexed between 1...NUMBER_OF_MEMBERS
+    pub fn share_indices(&self, member: usize) -> Result<RangeInclusive<usize>, Error> {
+        if member < 1 || member > self.number_of_members() {
+            return Err(Error::InvalidIndex { index: member });
+        }
+
+        let start: usize = self.weights[..member - 1].iter().sum();
+        Ok(start + 1..=start + self.weights[member - 1])
+    }
+
+    // the member holding the share with the given index
+    pub fn member_of(&self, index: usize) -> Result<usize, Error> {
+        let mut end = 0;
+        for (i, w) in self.weights.iter().enumerate() {
+            end += w;
+            if index >= 1 && index <= end {
+                return Ok(i + 1);
+            }
+        }
+
+        Err(Error::InvalidIndex { index })
+    }
+
+    // one encryption key per share: k * pk_i for k = 1...w_i
+    pub fn share_public_keys(&self, public_keys: &[GkG1]) -> Result<Vec<GkG1>, Error> {
+        check_length(self.number_of_members(), public_keys.len())?;
+
+        let keys = public_keys
+            .iter()
+            .zip(self.weights.iter())
+            .flat_map(|(pk, w)| (1..=*w).map(move |k| (pk * GkScalar::from(k as u64)).to_affine()))
+            .collect();
+
+        Ok(keys)
+    }
+}
+
+impl MemberKey {
+    // the keys of member i for each of its shares, e.g. to complain about a single share
+    pub fn weighted_keys(
+        &self,
+        config: &WeightedConfig,
+        member: usize,
+    ) -> Result<Vec<(usize, MemberKey)>, Error> {
+        let keys = config
+            .share_indices(member)?
+            .enumerate()
+            .map(|(k, index)| {
+                let k = GkScalar::from(k as u64 + 1);
+                let key =
+                    MemberKey::new(*self.secret_key() * k, (self.public_key() * k).to_affine());
+                (index, key)
+            })
+            .collect();
+
+        Ok(keys)
+    }
+
+    // decrypt and aggregate every share of member i
+    pub fn weighted_share_keys(
+        &self,
+        config: &WeightedConfig,
+        member: usize,
+        pps: &[&DkgMemberPublicParams],
+    ) -> Result<Vec<DkgShareKey>, Error> {
+        let dkg_config = config.dkg_config()?;
+        self.weighted_keys(config, member)?
+            .iter()
+            .map(|(index, key)| key.dkg_share_key(&dkg_config, *index, pps))
+            .collect()
+    }
+}
+
+impl DkgMemberParams {
+    // deal W shares, encrypted to the keys of share_public_keys
+    pub fn new_weighted(
+        config: &WeightedConfig,
+        public_keys: &[GkG1],
+        rng: impl RngCore,
+    ) -> Result<Self, Error> {
+        let share_public_keys = config.share_public_keys(public_keys)?;
+        Self::new(config.dkg_config()?, share_public_keys, rng)
+    }
+}
+
+// combine partial evaluations from members with a total weight of at least threshold;
+// the evaluations can come in any order, an index given more than once counts once and the
+// first threshold shares by index are used
+pub fn combine_weighted_partial_evaluations(
+    config: &WeightedConfig,
+    sigmas: &[PartialEval],
+) -> Result<PseudoRandom, Error> {
+    let mut sorted = sigmas.to_vec();
+    sorted.sort_by_key(|sigma| sigma.index);
+    sorted.dedup_by_key(|sigma| sigma.index);
+
+    if sorted.len() < config.threshold() {
+        return Err(Error::NotEnoughEvaluations {
+            valid: sorted.len(),
+            required: config.threshold(),
+        });
+    }
+    sorted.truncate(config.threshold());
+
+    combine_partial_evaluations(&config.dkg_config()?, &sorted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::EvalDomain;
+    use crate::dkg_global_public_params;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_weighted_config() {
+        let config = WeightedConfig::new(4, vec![1, 2, 3]).unwrap();
+        assert_eq!(config.total_weight(), 6);
+        assert_eq!(config.dkg_config().unwrap().number_of_members(), 6);
+        assert_eq!(config.share_indices(1).unwrap(), 1..=1);
+        assert_eq!(config.share_indices(3).unwrap(), 4..=6);
+        assert!(config.share_indices(4).is_err());
+        assert_eq!(config.member_of(3).unwrap(), 2);
+        assert_eq!(config.member_of(6).unwrap(), 3);
+        assert!(config.member_of(0).is_err());
+        assert!(config.member_of(7).is_err());
+
+        assert!(WeightedConfig::new(7, vec![1, 2, 3]).is_err());
+        assert!(WeightedConfig::new(2, vec![1, 0, 3]).is_err());
+        assert!(WeightedConfig::new(0, vec![1]).is_err());
+        assert!(WeightedConfig::new(2, vec![usize::MAX, 1]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_weighted_config_serde() {
+        let config = WeightedConfig::new(4, vec![1, 2, 3]).unwrap();
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(json, r#"{"threshold":4,"weights":[1,2,3]}"#);
+        let decoded: WeightedConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, config);
+
+        // configurations rejected by new are rejected when deserialised
+        for json in [
+            r#"{"threshold":7,"weights":[1,2,3]}"#,
+            r#"{"threshold":2,"weights":[1,0,3]}"#,
+            r#"{"threshold":0,"weights":[1]}"#,
+            r#"{"threshold":1,"weights":[]}"#,
+        ] {
+            assert!(serde_json::from_str::<WeightedConfig>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_weighted_dkg() {
+        let mut rng = OsRng;
+        let config = WeightedConfig::new(4, vec![1, 2, 3]).unwrap();
+        let dkg_config = config.dkg_config().unwrap();
+
+        let members: Vec<_> = (0..3).map(|_| MemberKey::random(&mut rng)).collect();
+        let pks: Vec<_> = members.iter().map(|m| m.public_key()).collect();
+
+        let dkgs: Vec<_> = (0..3)
+            .map(|_| DkgMemberParams::new_weighted(&config, &pks, &mut rng).unwrap())
+            .collect();
+        let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
+        let gpp = dkg_global_public_params(&dkg_config, &dkgs_pub).unwrap();
+
+        // the shares of one member are encrypted under different keys
+        let ciphers = &dkgs_pub[0].ciphers;
+        let shares = dkgs[0].shares.expose_secret();
+        assert_ne!(ciphers[4] - shares[4], ciphers[5] - shares[5]);
+
+        let share_keys: Vec<Vec<_>> = members
+            .iter()
+            .enumerate()
+            .map(|(i, m)| m.weighted_share_keys(&config, i + 1, &dkgs_pub).unwrap())
+            .collect();
+        for (i, keys) in share_keys.iter().enumerate() {
+            assert_eq!(keys.len(), config.weights()[i]);
+            for key in keys.iter() {
+                assert_eq!(config.member_of(key.index()).unwrap(), i + 1);
+                key.verify(&dkg_config, &gpp.verify_keys).unwrap();
+            }
+        }
+
+        // members 3 and 1 have a total weight of 4
+        let input = b"weighted random";
+        let sigmas: Vec<_> = share_keys[2]
+            .iter()
+            .chain(share_keys[0].iter())
+            .map(|key| key.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        let pseudo = combine_weighted_partial_evaluations(&config, &sigmas).unwrap();
+        pseudo
+            .verify(&EvalDomain::default(), input, &gpp.g2a)
+            .unwrap();
+
+        // a duplicated share is used once
+        let mut duplicated = sigmas.clone();
+        duplicated.push(sigmas[3].clone());
+        let v = combine_weighted_partial_evaluations(&config, &duplicated).unwrap();
+        assert_eq!(v.value, pseudo.value);
+
+        // members 1 and 2 only have a total weight of 3
+        let sigmas: Vec<_> = share_keys[0]
+            .iter()
+            .chain(share_keys[1].iter())
+            .map(|key| key.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        assert!(matches!(
+            combine_weighted_partial_evaluations(&config, &sigmas),
+            Err(Error::NotEnoughEvaluations { .. })
+        ));
+
+        // and a duplicated share does not make up for the missing weight
+        let mut duplicated = sigmas.clone();
+        duplicated.push(sigmas[0].clone());
+        assert!(matches!(
+            combine_weighted_partial_evaluations(&config, &duplicated),
+            Err(Error::NotEnoughEvaluations { valid: 3, .. })
+        ));
+    }
+}
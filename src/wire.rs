@@ -0,0 +1,439 @@
+// compact binary encoding for exchanging artifacts between members;
+// every encoding starts with WIRE_VERSION and a type tag, points are compressed
+// and decoding rejects non-canonical field elements, points and trailing bytes
+use crate::dkg::{DkgShareKey, PartialEval, PartialEvalProof, PseudoRandom};
+use crate::error::Error;
+use crate::secret::Secret;
+use crate::utils::is_in_g2_subgroup;
+use crate::{DkgMemberPublicParams, MemberKey};
+use halo2wrong::curves::bn256::{Fr as BnScalar, G1Affine as BnG1, G2Affine as BnG2};
+use halo2wrong::curves::ff::PrimeField;
+use halo2wrong::curves::group::{Curve, GroupEncoding};
+use halo2wrong::curves::grumpkin::{Fr as GkScalar, G1Affine as GkG1};
+use halo2wrong::curves::CurveAffine;
+
+pub const WIRE_VERSION: u8 = 1;
+
+const TAG_SCALAR: u8 = 0x01;
+const TAG_MEMBER_KEY: u8 = 0x02;
+const TAG_SHARE_KEY: u8 = 0x03;
+const TAG_PARTIAL_EVAL: u8 = 0x04;
+const TAG_PSEUDO_RANDOM: u8 = 0x05;
+const TAG_MEMBER_PUBLIC_PARAMS: u8 = 0x06;
+const TAG_LIST: u8 = 0x40;
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidEncoding(reason.to_string())
+}
+
+mod private {
+    use super::*;
+
+    pub struct Reader<'a> {
+        bytes: &'a [u8],
+    }
+
+    impl<'a> Reader<'a> {
+        pub fn new(bytes: &'a [u8]) -> Self {
+            Reader { bytes }
+        }
+
+        pub fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+            if self.bytes.len() < len {
+                return Err(invalid("unexpected end of input"));
+            }
+            let (head, tail) = self.bytes.split_at(len);
+            self.bytes = tail;
+            Ok(head)
+        }
+
+        pub fn finish(&self) -> Result<(), Error> {
+            if !self.bytes.is_empty() {
+                return Err(invalid("trailing bytes"));
+            }
+            Ok(())
+        }
+    }
+
+    // the body of an encoding without the version and tag
+    pub trait Body: Sized {
+        const TAG: u8;
+        type Encoding: From<Vec<u8>> + super::WireBytes;
+        fn write(&self, out: &mut Vec<u8>);
+        fn read(r: &mut Reader) -> Result<Self, Error>;
+    }
+}
+
+use private::{Body, Reader};
+
+// the bytes of an encoding; the encodings of secret keys are held in Secret and wiped when dropped
+pub trait WireBytes {
+    fn wire_bytes(&self) -> &[u8];
+}
+
+impl WireBytes for Vec<u8> {
+    fn wire_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl WireBytes for Secret<Vec<u8>> {
+    fn wire_bytes(&self) -> &[u8] {
+        self.expose_secret()
+    }
+}
+
+pub trait Wire: Sized {
+    type Encoding: WireBytes;
+    fn to_wire(&self) -> Self::Encoding;
+    fn from_wire(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+// the keys fit into the initial capacity, so no copies of them are left behind by reallocations
+const INITIAL_CAPACITY: usize = 128;
+
+impl<T: Body> Wire for T {
+    type Encoding = T::Encoding;
+
+    fn to_wire(&self) -> Self::Encoding {
+        let mut out = Vec::with_capacity(INITIAL_CAPACITY);
+        out.extend([WIRE_VERSION, T::TAG]);
+        self.write(&mut out);
+        out.into()
+    }
+
+    fn from_wire(bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(bytes);
+        let header = r.take(2)?;
+        if header[0] != WIRE_VERSION {
+            return Err(Error::InvalidEncoding(format!(
+                "unsupported wire version {}",
+                header[0]
+            )));
+        }
+        if header[1] != T::TAG {
+            return Err(Error::InvalidEncoding(format!(
+                "unexpected type tag {}",
+                header[1]
+            )));
+        }
+
+        let v = T::read(&mut r)?;
+        r.finish()?;
+        Ok(v)
+    }
+}
+
+// lengths and member indices are far below 2^32; never truncate one into a different value
+fn write_u32(out: &mut Vec<u8>, v: usize) {
+    let v = u32::try_from(v).expect("lengths and indices are encoded in 32 bits");
+    out.extend(v.to_le_bytes());
+}
+
+fn read_u32(r: &mut Reader) -> Result<usize, Error> {
+    let bytes = r.take(4)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+}
+
+fn write_field<F: PrimeField>(out: &mut Vec<u8>, f: &F) {
+    out.extend(f.to_repr().as_ref());
+}
+
+fn read_field<F: PrimeField>(r: &mut Reader) -> Result<F, Error> {
+    let mut repr = F::Repr::default();
+    let len = repr.as_ref().len();
+    repr.as_mut().copy_from_slice(r.take(len)?);
+    Option::from(F::from_repr(repr)).ok_or_else(|| invalid("non-canonical field element"))
+}
+
+fn write_point<C: GroupEncoding>(out: &mut Vec<u8>, p: &C) {
+    out.extend(p.to_bytes().as_ref());
+}
+
+// decompress a point and reject encodings that do not round trip, e.g. with unused flag bits set
+fn read_point<C: GroupEncoding>(r: &mut Reader) -> Result<C, Error> {
+    let mut repr = C::Repr::default();
+    let len = repr.as_ref().len();
+    repr.as_mut().copy_from_slice(r.take(len)?);
+    let p: C = Option::from(C::from_bytes(&repr)).ok_or_else(|| invalid("invalid point"))?;
+    if p.to_bytes().as_ref() != repr.as_ref() {
+        return Err(invalid("non-canonical point encoding"));
+    }
+    Ok(p)
+}
+
+fn read_g2(r: &mut Reader) -> Result<BnG2, Error> {
+    let p: BnG2 = read_point(r)?;
+    if !is_in_g2_subgroup(&p) {
+        return Err(invalid("Bn256 G2 point is not in the prime order subgroup"));
+    }
+    Ok(p)
+}
+
+impl Body for BnScalar {
+    const TAG: u8 = TAG_SCALAR;
+    type Encoding = Vec<u8>;
+
+    fn write(&self, out: &mut Vec<u8>) {
+        write_field(out, self);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        read_field(r)
+    }
+}
+
+// the element tag is repeated in the body so nested lists are unambiguous
+impl<T: Body> Body for Vec<T> {
+    const TAG: u8 = TAG_LIST;
+    type Encoding = Vec<u8>;
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.push(T::TAG);
+        write_u32(out, self.len());
+        for v in self.iter() {
+            v.write(out);
+        }
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        if r.take(1)?[0] != T::TAG {
+            return Err(invalid("unexpected list element tag"));
+        }
+        let len = read_u32(r)?;
+        // do not trust the length for allocation
+        let mut vs = vec![];
+        for _ in 0..len {
+            vs.push(T::read(r)?);
+        }
+        Ok(vs)
+    }
+}
+
+impl Body for MemberKey {
+    const TAG: u8 = TAG_MEMBER_KEY;
+    type Encoding = Secret<Vec<u8>>;
+
+    fn write(&self, out: &mut Vec<u8>) {
+        write_field(out, self.secret_key());
+        write_point(out, &self.public_key());
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        let sk: GkScalar = read_field(r)?;
+        let pk: GkG1 = read_point(r)?;
+        if (GkG1::generator() * sk).to_affine() != pk {
+            return Err(invalid("public key does not match secret key"));
+        }
+        Ok(MemberKey::new(sk, pk))
+    }
+}
+
+impl Body for DkgShareKey {
+    const TAG: u8 = TAG_SHARE_KEY;
+    type Encoding = Secret<Vec<u8>>;
+
+    fn write(&self, out: &mut Vec<u8>) {
+        write_u32(out, self.index());
+        out.extend(self.epoch().to_le_bytes());
+        write_field(out, self.secret_key());
+        write_point(out, &self.verify_key());
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        let index = read_u32(r)?;
+        let epoch = u64::from_le_bytes(r.take(8)?.try_into().unwrap());
+        let sk: BnScalar = read_field(r)?;
+        let vk: BnG1 = read_point(r)?;
+        if (BnG1::generator() * sk).to_affine() != vk {
+            return Err(invalid("verification key does not match secret key"));
+        }
+        Ok(DkgShareKey::new_with_epoch(index, sk, vk, epoch))
+    }
+}
+
+impl Body for PartialEval {
+    const TAG: u8 = TAG_PARTIAL_EVAL;
+    type Encoding = Vec<u8>;
+
+    fn write(&self, out: &mut Vec<u8>) {
+        write_u32(out, self.index);
+        write_point(out, &self.value);
+        write_field(out, &self.proof.z);
+        write_field(out, &self.proof.c);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        let index = read_u32(r)?;
+        let value = read_point(r)?;
+        let z = read_field(r)?;
+        let c = read_field(r)?;
+        Ok(PartialEval {
+            index,
+            value,
+            proof: PartialEvalProof { z, c },
+        })
+    }
+}
+
+impl Body for PseudoRandom {
+    const TAG: u8 = TAG_PSEUDO_RANDOM;
+    type Encoding = Vec<u8>;
+
+    fn write(&self, out: &mut Vec<u8>) {
+        write_point(out, &self.proof);
+        write_u32(out, self.value.len());
+        out.extend(&self.value);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        let proof = read_point(r)?;
+        let len = read_u32(r)?;
+        let value = r.take(len)?.to_vec();
+        Ok(PseudoRandom::new(proof, value))
+    }
+}
+
+impl Body for DkgMemberPublicParams {
+    const TAG: u8 = TAG_MEMBER_PUBLIC_PARAMS;
+    type Encoding = Vec<u8>;
+
+    fn write(&self, out: &mut Vec<u8>) {
+        write_u32(out, self.public_shares.len());
+        for gs in self.public_shares.iter() {
+            write_point(out, gs);
+        }
+        write_u32(out, self.ciphers.len());
+        for c in self.ciphers.iter() {
+            write_field(out, c);
+        }
+        write_point(out, &self.gr);
+        write_point(out, &self.ga);
+        write_point(out, &self.g2a);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        let len = read_u32(r)?;
+        let mut public_shares = vec![];
+        for _ in 0..len {
+            public_shares.push(read_point(r)?);
+        }
+        let len = read_u32(r)?;
+        let mut ciphers = vec![];
+        for _ in 0..len {
+            ciphers.push(read_field(r)?);
+        }
+        let gr = read_point(r)?;
+        let ga = read_point(r)?;
+        let g2a = read_g2(r)?;
+
+        Ok(DkgMemberPublicParams {
+            public_shares,
+            ciphers,
+            gr,
+            ga,
+            g2a,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::{DkgConfig, EvalDomain};
+    use crate::{dkg_global_public_params, DkgMemberParams};
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_wire() {
+        let mut rng = OsRng;
+
+        let dkg_config = DkgConfig::new(3, 5).unwrap();
+        let members: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| MemberKey::random(&mut rng))
+            .collect();
+        let pks: Vec<_> = members.iter().map(|m| m.public_key()).collect();
+        let dkgs: Vec<_> = (0..dkg_config.number_of_members())
+            .map(|_| DkgMemberParams::new(dkg_config, pks.clone(), &mut rng).unwrap())
+            .collect();
+        let dkgs_pub: Vec<_> = dkgs.iter().map(|dkg| dkg.member_public_params()).collect();
+        let gpp = dkg_global_public_params(&dkg_config, &dkgs_pub).unwrap();
+        let shares: Vec<_> = members
+            .iter()
+            .enumerate()
+            .map(|(i, m)| m.dkg_share_key(&dkg_config, i + 1, &dkgs_pub).unwrap())
+            .collect();
+
+        let member = MemberKey::from_wire(members[0].to_wire().expose_secret()).unwrap();
+        assert_eq!(member.secret_key(), members[0].secret_key());
+        assert_eq!(member.public_key(), members[0].public_key());
+
+        let pp = DkgMemberPublicParams::from_wire(&dkgs_pub[0].to_wire()).unwrap();
+        assert_eq!(pp.public_shares, dkgs_pub[0].public_shares);
+        assert_eq!(pp.ciphers, dkgs_pub[0].ciphers);
+        assert_eq!(pp.gr, dkgs_pub[0].gr);
+        assert_eq!(pp.ga, dkgs_pub[0].ga);
+        assert_eq!(pp.g2a, dkgs_pub[0].g2a);
+
+        let share = DkgShareKey::from_wire(shares[0].to_wire().expose_secret()).unwrap();
+        assert_eq!(share.index(), 1);
+        assert_eq!(share.secret_key(), shares[0].secret_key());
+        assert_eq!(share.epoch(), shares[0].epoch());
+
+        let input = b"wire";
+        let sigmas: Vec<_> = shares
+            .iter()
+            .map(|s| s.evaluate(&EvalDomain::default(), input, &mut rng))
+            .collect();
+        let bytes = sigmas.to_wire();
+        let decoded = Vec::<PartialEval>::from_wire(&bytes).unwrap();
+        for (sigma, vk) in decoded.iter().zip(gpp.verify_keys.iter()) {
+            sigma
+                .verify(&dkg_config, &EvalDomain::default(), input, vk)
+                .unwrap();
+        }
+
+        let pseudo = crate::combine_partial_evaluations(&dkg_config, &decoded[0..3]).unwrap();
+        let decoded = PseudoRandom::from_wire(&pseudo.to_wire()).unwrap();
+        decoded
+            .verify(&EvalDomain::default(), input, &gpp.g2a)
+            .unwrap();
+        assert_eq!(decoded.value, pseudo.value);
+
+        let instance = dkgs[0].instance();
+        let decoded = Vec::<Vec<BnScalar>>::from_wire(&instance.to_wire()).unwrap();
+        assert_eq!(decoded, instance);
+
+        // compressed points are much smaller than the hex json encoding
+        let sigma_bytes = sigmas[0].to_wire();
+        assert_eq!(sigma_bytes.len(), 2 + 4 + 32 * 3);
+
+        // strict decoding
+        let mut bad = sigma_bytes.clone();
+        bad[0] = WIRE_VERSION + 1;
+        assert!(PartialEval::from_wire(&bad).is_err());
+        assert!(DkgShareKey::from_wire(&sigma_bytes).is_err());
+        let mut bad = sigma_bytes.clone();
+        bad.push(0);
+        assert!(PartialEval::from_wire(&bad).is_err());
+        assert!(PartialEval::from_wire(&sigma_bytes[..sigma_bytes.len() - 1]).is_err());
+        // z = modulus is not canonical
+        let mut bad = sigma_bytes.clone();
+        let modulus = (-BnScalar::one()).to_repr();
+        bad[38..70].copy_from_slice(&modulus);
+        bad[38] += 1;
+        assert!(PartialEval::from_wire(&bad).is_err());
+        // a share key whose verification key does not match
+        let mut bad = shares[0].to_wire().expose_secret().clone();
+        let vk = shares[1].verify_key().to_bytes();
+        let len = bad.len();
+        bad[len - 32..].copy_from_slice(vk.as_ref());
+        assert!(DkgShareKey::from_wire(&bad).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_write_u32_overflow() {
+        write_u32(&mut vec![], u32::MAX as usize + 1);
+    }
+}
@@ -81,7 +81,7 @@ describe('ZKDVRF on-chain tests', async () => {
         Halo2Verifier = await(await ethers.getContractFactory('contracts/Halo2Verifier.sol:Halo2Verifier')).deploy()
         Halo2VerifyingKey = await(await ethers.getContractFactory('contracts/Halo2VerifyingKey-3-5-18-g2.sol:Halo2VerifyingKey')).deploy()
         GlobalPublicParams = await(await ethers.getContractFactory('GlobalPublicParams')).deploy()
-        PseudoRand = await(await ethers.getContractFactory('PseudoRand')).deploy()
+        PseudoRand = await(await ethers.getContractFactory('PseudoRand')).deploy('0x')
         Zkdvrf = await (
             await ethers.getContractFactory('zkdvrf')
         ).deploy(3, 5, Halo2Verifier.address, Halo2VerifyingKey.address, GlobalPublicParams.address, PseudoRand.address, minDeposit)
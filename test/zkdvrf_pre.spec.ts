@@ -154,7 +154,7 @@ describe('ZKDVRF (with precomputation of hash) on-chain tests', async () => {
         Halo2Verifier = await (await ethers.getContractFactory('contracts/Halo2Verifier.sol:Halo2Verifier')).deploy()
         Halo2VerifyingKey = await (await ethers.getContractFactory('contracts/Halo2VerifyingKey-3-5-18-g2.sol:Halo2VerifyingKey')).deploy()
         GlobalPublicParams = await (await ethers.getContractFactory('GlobalPublicParams')).deploy()
-        PseudoRand = await (await ethers.getContractFactory('PseudoRand')).deploy()
+        PseudoRand = await (await ethers.getContractFactory('PseudoRand')).deploy('0x')
 
         account1 = (await ethers.getSigners())[0]
         account2 = (await ethers.getSigners())[1]